@@ -1,25 +1,198 @@
+mod binding;
+mod defaults;
+mod layers;
+mod settings;
+mod sprite;
+mod validate;
+mod watch;
+
+pub use self::{
+    binding::{AxisSide, Binding, BindingEvent},
+    layers::{ConfigLayers, LayeredConfig},
+    settings::UserSettings,
+    sprite::{AnimationState, SpriteSheet},
+    watch::ConfigWatcher,
+};
+
 use crate::prelude::*;
 
 #[derive(geng::asset::Load)]
 pub struct Assets {
-    #[load(options(filter = "ugli::Filter::Nearest"))]
-    pub sword: Rc<ugli::Texture>,
-    #[load(options(filter = "ugli::Filter::Nearest"))]
-    pub mannequin: Rc<ugli::Texture>,
+    pub sprites: Sprites,
+    #[load(serde, path = "particles.ron")]
+    pub particles: ParticleEffects,
+    pub sounds: Sounds,
+}
+
+#[derive(geng::asset::Load)]
+pub struct Sounds {
+    pub swing: geng::Sound,
+    pub hit: geng::Sound,
+    pub parry: geng::Sound,
+    pub death: geng::Sound,
+    pub footstep: geng::Sound,
+    #[load(options(looped = "true"))]
+    pub music: geng::Sound,
+}
+
+#[derive(geng::asset::Load)]
+pub struct Sprites {
+    pub player: SpriteSheet,
+    pub mannequin: SpriteSheet,
+    pub sword: SpriteSheet,
+}
+
+/// Particle emitters fired together for each kind of effect.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticleEffects {
+    pub hit: Vec<Emitter>,
+    pub parry: Vec<Emitter>,
+    /// A mannequin falling apart.
+    pub death: Vec<Emitter>,
+    /// Kicked up by walking.
+    pub dust: Vec<Emitter>,
+}
+
+/// Describes a burst of particles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Emitter {
+    /// Number of particles per burst.
+    pub count: usize,
+    /// Range of the particle lifetime in seconds.
+    pub lifetime: (f32, f32),
+    /// Range of the initial speed.
+    pub speed: (f32, f32),
+    /// Maximum deviation (in degrees) from the emission direction to either side.
+    pub spread: f32,
+    /// Fraction of the velocity lost per second.
+    pub drag: f32,
+    /// Size keyframes evenly spread over the lifetime.
+    pub size: Vec<f32>,
+    /// Color keyframes evenly spread over the lifetime.
+    pub color: Vec<Color>,
 }
 
-#[derive(geng::asset::Load, Debug, Clone, Serialize, Deserialize)]
+/// Read and parse a `ron` file with the geng file loader, so it works on every platform.
+pub async fn load_ron<T: DeserializeOwned>(path: impl AsRef<std::path::Path>) -> Result<T, String> {
+    let path = path.as_ref();
+    let source = file::load_string(path)
+        .await
+        .map_err(|err| format!("Failed to read {:?}: {}", path, err))?;
+    ron::from_str(&source).map_err(|err| format!("Failed to parse {:?}: {}", path, err))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Level {
+    /// Playable area, entities cannot leave it.
+    pub arena: Aabb2<Coord>,
+    #[serde(default)]
+    pub opponents: Vec<OpponentSpawn>,
+    /// Where the waves of the survival mode come from.
+    /// Defaults to the corners of the arena.
+    #[serde(default)]
+    pub spawn_points: Vec<vec2<Coord>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpponentSpawn {
+    pub position: vec2<Coord>,
+    /// Name of the archetype from [AiConfig::archetypes].
+    pub archetype: String,
+}
+
+#[derive(geng::asset::Load, Debug, Clone, Default, Serialize, Deserialize)]
 #[load(serde = "ron")]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
+    pub display: DisplayConfig,
+    pub audio: AudioConfig,
+    pub camera: CameraConfig,
     pub cursor: CursorConfig,
     pub controls: Controls,
     pub palette: Palette,
     pub player: PlayerConfig,
+    pub dash: DashConfig,
+    pub combat: CombatConfig,
+    pub clash: ClashConfig,
+    pub charge: ChargeConfig,
+    pub ai: AiConfig,
+    pub throw: ThrowConfig,
+    pub projectiles: ProjectilesConfig,
+    pub survival: SurvivalConfig,
     pub weapon: WeaponConfig,
+    /// Other weapons, picked by name for the simulated fighters.
+    pub arsenal: Vec<WeaponConfig>,
+    pub mannequin: MannequinConfig,
+}
+
+impl Config {
+    /// The weapon with the name, the main [Config::weapon] included.
+    pub fn weapon_by_name(&self, name: &str) -> Option<&WeaponConfig> {
+        std::iter::once(&self.weapon)
+            .chain(&self.arsenal)
+            .find(|weapon| weapon.name == name)
+    }
+
+    /// The weapon a fighter is armed with, `None` or an unknown name is the main weapon.
+    pub fn armed(&self, name: Option<&str>) -> &WeaponConfig {
+        name.and_then(|name| self.weapon_by_name(name))
+            .unwrap_or(&self.weapon)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Display")]
+pub struct DisplayConfig {
+    /// Internal resolution the game is rendered at.
+    pub resolution: vec2<usize>,
+    /// How the rendered image is scaled to the window.
+    pub scaling: ScalingMode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScalingMode {
+    /// Scale by the largest integer factor that fits, keeping pixels square and sharp.
+    PixelPerfect,
+    /// Scale to fit the window keeping the aspect ratio.
+    Fit,
+    /// Stretch to fill the whole window.
+    Stretch,
+}
+
+/// Volumes in range `0..=1`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Audio")]
+pub struct AudioConfig {
+    pub master_volume: R32,
+    pub sfx_volume: R32,
+    pub music_volume: R32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Camera")]
+pub struct CameraConfig {
+    /// Vertical field of view when there is nothing else to fit.
+    pub fov_min: Coord,
+    /// The camera never zooms out further than this.
+    pub fov_max: Coord,
+    /// Extra space around the combatants when zooming out to fit them.
+    pub fit_margin: Coord,
+    /// The player can move this far from the camera center before the camera follows.
+    pub dead_zone: Coord,
+    /// How fast the camera catches up to the target, per second.
+    pub smoothing: R32,
+    /// Trauma added per unit of hit power.
+    pub hit_trauma: R32,
+    /// Trauma lost per second.
+    pub trauma_decay: R32,
+    /// Offset of the camera at full trauma.
+    pub shake_offset: Coord,
+    /// Rotation (in degrees) of the camera at full trauma.
+    pub shake_angle: R32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename = "Cursor")]
+#[serde(default, deny_unknown_fields, rename = "Cursor")]
 pub struct CursorConfig {
     pub sensitivity: Coord,
     pub trail_time: Time,
@@ -27,35 +200,428 @@ pub struct CursorConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename = "Weapon")]
+#[serde(default, deny_unknown_fields, rename = "Weapon")]
 pub struct WeaponConfig {
+    /// Displayed in the HUD.
+    pub name: String,
     pub acceleration: Coord,
     pub swing_boost: Coord,
     pub speed_max: Coord,
+    /// Heavier weapons hit harder at the same speed.
+    pub mass: R32,
     pub power_min: R32,
     pub power_max: R32,
+    /// Status effects applied to the targets of the attacks.
+    pub on_hit: Vec<EffectConfig>,
+}
+
+/// A status effect applied by a weapon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EffectConfig {
+    pub effect: StatusEffect,
+    pub duration: Time,
+    /// Weaker hits do not apply the effect.
+    pub min_power: R32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StatusEffect {
+    /// Loses health over time.
+    Bleed {
+        /// Damage per second.
+        damage: Hp,
+    },
+    /// Cannot charge, dash, feint or throw.
+    Stun,
+    /// Moves and swings slower.
+    Slow {
+        /// Multiplier of the walk speed and the weapon acceleration.
+        factor: R32,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename = "Player")]
+#[serde(default, deny_unknown_fields, rename = "Player")]
 pub struct PlayerConfig {
     pub walk_speed: Coord,
     pub acceleration: Coord,
+    pub health: Hp,
+    pub stamina: R32,
+    /// Stamina restored per second.
+    pub stamina_regen: R32,
+    /// Maximum time between hits to continue the combo.
+    pub combo_window: Time,
+    /// Time the player stays down after dying.
+    pub respawn_time: Time,
+}
+
+/// A quick burst of movement, making the fighter briefly invulnerable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Dash")]
+pub struct DashConfig {
+    pub speed: Coord,
+    /// How long the burst velocity is kept.
+    pub duration: Time,
+    /// Minimum time between the starts of two dashes.
+    pub cooldown: Time,
+    pub stamina_cost: R32,
+    /// Time since the start of the dash during which weapons do not hit.
+    pub invulnerability: Time,
+}
+
+/// Rules of the fights between fighters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Combat")]
+pub struct CombatConfig {
+    /// Width (in degrees) of the cone behind a fighter where hits count as backstabs.
+    pub backstab_angle: R32,
+    /// Damage multiplier of the backstabs.
+    pub backstab_multiplier: R32,
+    /// Width (in degrees) of the cone in front of a fighter where defending blocks attacks.
+    pub block_angle: R32,
+    /// Damage per unit of the weapon momentum at impact and of the swing power.
+    pub damage_scale: R32,
+    /// Stamina spent to cancel a charge without swinging.
+    pub feint_cost: R32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Clash")]
+pub struct ClashConfig {
+    /// Fraction of the approach speed kept when the blades bounce off each other.
+    pub restitution: R32,
+    /// Blades meeting slower than this lock in a bind instead of bouncing.
+    /// Zero disables binding.
+    pub bind_speed: Coord,
+    /// Distance between the cursor and the bound weapon that breaks the bind.
+    pub disengage: Coord,
+    /// Minimum time between two clashes of the same weapon.
+    pub cooldown: Time,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Charge")]
+pub struct ChargeConfig {
+    pub curve: ChargeCurve,
+    /// Holding time to reach the maximum power.
+    pub full_time: Time,
+    /// How long the full charge can be held before it drops.
+    pub hold_time: Time,
+    /// Fraction of the charge kept after holding for too long.
+    pub overcharge_power: R32,
+}
+
+/// How the charge grows with the holding time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChargeCurve {
+    Linear,
+    /// Fast at first, slowing down towards the full charge.
+    Sqrt,
+    /// Slow at first, rewarding the full charge.
+    EaseIn,
+}
+
+/// Throwing projectiles with a flick of the cursor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Throw")]
+pub struct ThrowConfig {
+    /// What the player throws.
+    pub projectile: ProjectileKind,
+    /// Latest part of the cursor history that determines the direction and the speed.
+    pub gesture_time: Time,
+    /// Projectile speed per unit of the cursor speed.
+    pub speed_scale: R32,
+    pub speed_min: Coord,
+    pub speed_max: Coord,
+    pub cooldown: Time,
+    pub stamina_cost: R32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProjectileKind {
+    Dagger,
+    Arrow,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Projectiles")]
+pub struct ProjectilesConfig {
+    pub dagger: ProjectileConfig,
+    pub arrow: ProjectileConfig,
+}
+
+impl ProjectilesConfig {
+    pub fn get(&self, kind: ProjectileKind) -> &ProjectileConfig {
+        match kind {
+            ProjectileKind::Dagger => &self.dagger,
+            ProjectileKind::Arrow => &self.arrow,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Projectile")]
+pub struct ProjectileConfig {
+    pub radius: Coord,
+    /// Heavier projectiles hit harder at the same speed.
+    pub mass: R32,
+    /// Time until the projectile falls to the ground and disappears.
+    pub lifetime: Time,
+    /// Drawn length along the flight direction.
+    pub length: Coord,
+    /// Status effects applied to the targets hit.
+    pub on_hit: Vec<EffectConfig>,
+}
+
+/// Waves of opponents in the survival mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Survival")]
+pub struct SurvivalConfig {
+    /// Number of opponents in the first wave.
+    pub first_wave: usize,
+    /// Opponents added with every next wave.
+    pub wave_growth: usize,
+    /// Pause between clearing a wave and the next one.
+    pub wave_delay: Time,
+    /// Increase of the opponent health multiplier per wave.
+    pub health_growth: R32,
+    /// Increase of the opponent damage multiplier per wave.
+    pub damage_growth: R32,
+    /// Score for every opponent killed.
+    pub kill_score: u64,
+    /// Increase of the score multiplier per hit in the current combo.
+    pub combo_bonus: R32,
+    /// Number of entries kept in the high-score table.
+    pub high_scores: usize,
+}
+
+/// Behaviour of the computer-controlled fighters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Ai")]
+pub struct AiConfig {
+    /// Utility bonus of the current action, keeps the fighters from dithering.
+    pub commitment: R32,
+    /// Distance to the target within which the fighters parry.
+    pub guard_range: Coord,
+    /// Random variation of the reaction and charge times, as a fraction of them.
+    pub jitter: R32,
+    /// Kinds of the computer-controlled fighters, the first one is the fallback.
+    pub archetypes: Vec<ArchetypeConfig>,
+}
+
+impl AiConfig {
+    /// The archetype by its name.
+    pub fn archetype(&self, name: &str) -> Option<&ArchetypeConfig> {
+        self.archetypes
+            .iter()
+            .find(|archetype| archetype.name == name)
+    }
+}
+
+/// A kind of computer-controlled fighter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArchetypeConfig {
+    pub name: String,
+    /// Multiplier of the base health.
+    pub health: R32,
+    /// Multiplier of the damage dealt.
+    pub damage: R32,
+    /// Delay before reacting to the target charging or swinging.
+    pub reaction_time: Time,
+    /// Distance the fighter tries to keep from the target.
+    pub preferred_range: Coord,
+    /// How long the attacks are charged.
+    pub charge_time: Time,
+    /// Minimum time between two attacks.
+    pub attack_cooldown: Time,
+    /// Power of the parries.
+    pub guard_power: R32,
+    /// Minimum time between two parries.
+    pub guard_cooldown: Time,
+    pub weights: UtilityWeights,
+}
+
+/// Multipliers of the action utilities, zero disables the action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UtilityWeights {
+    pub approach: R32,
+    pub strafe: R32,
+    pub attack: R32,
+    pub parry: R32,
+    pub retreat: R32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename = "Mannequin")]
+pub struct MannequinConfig {
+    pub health: Hp,
+    /// Time until a destroyed mannequin is rebuilt.
+    pub respawn_time: Time,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Controls {
-    pub attack: Vec<EventKey>,
-    pub defend: Vec<EventKey>,
-    pub up: Vec<EventKey>,
-    pub down: Vec<EventKey>,
-    pub left: Vec<EventKey>,
-    pub right: Vec<EventKey>,
+    pub tuning_panel: Vec<Binding>,
+    pub controls_menu: Vec<Binding>,
+    pub attack: Vec<Binding>,
+    pub defend: Vec<Binding>,
+    pub dash: Vec<Binding>,
+    /// Cancel the current charge.
+    pub feint: Vec<Binding>,
+    /// Throw a projectile in the direction of the cursor motion.
+    pub throw: Vec<Binding>,
+    pub up: Vec<Binding>,
+    pub down: Vec<Binding>,
+    pub left: Vec<Binding>,
+    pub right: Vec<Binding>,
+    pub debug: DebugControls,
+    pub training: TrainingControls,
+    pub survival: SurvivalControls,
+}
+
+/// Implements the lookup of the action bindings by name from a single table.
+macro_rules! controls_actions {
+    ($($name:literal => $($field:ident).+,)*) => {
+        impl Controls {
+            /// Names of all actions, same as their paths in the config.
+            pub const ACTIONS: &'static [&'static str] = &[$($name),*];
+
+            /// Bindings of the action by its name from [Self::ACTIONS].
+            pub fn action(&self, name: &str) -> Option<&Vec<Binding>> {
+                match name {
+                    $($name => Some(&self.$($field).+),)*
+                    _ => None,
+                }
+            }
+
+            /// Bindings of the action by its name from [Self::ACTIONS].
+            pub fn action_mut(&mut self, name: &str) -> Option<&mut Vec<Binding>> {
+                match name {
+                    $($name => Some(&mut self.$($field).+),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+controls_actions! {
+    "attack" => attack,
+    "defend" => defend,
+    "dash" => dash,
+    "feint" => feint,
+    "throw" => throw,
+    "up" => up,
+    "down" => down,
+    "left" => left,
+    "right" => right,
+    "tuning_panel" => tuning_panel,
+    "controls_menu" => controls_menu,
+    "training.reset" => training.reset,
+    "training.export" => training.export,
+    "survival.restart" => survival.restart,
+    "debug.arc" => debug.arc,
+    "debug.projection" => debug.projection,
+    "debug.reach" => debug.reach,
+    "debug.colliders" => debug.colliders,
 }
 
+impl Controls {
+    /// Find the keys bound to more than one action.
+    /// Returns the key with the names of the actions sharing it.
+    pub fn conflicts(&self) -> Vec<(Binding, Vec<&'static str>)> {
+        let mut conflicts: Vec<(Binding, Vec<&'static str>)> = Vec::new();
+        for &name in Self::ACTIONS {
+            for &key in self.action(name).into_iter().flatten() {
+                match conflicts.iter_mut().find(|(other, _)| *other == key) {
+                    Some((_, names)) => {
+                        if !names.contains(&name) {
+                            names.push(name);
+                        }
+                    }
+                    None => conflicts.push((key, vec![name])),
+                }
+            }
+        }
+        conflicts.retain(|(_, names)| names.len() > 1);
+        conflicts
+    }
+}
+
+/// Actions available in the training mode.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrainingControls {
+    /// Clear the recorded session.
+    pub reset: Vec<Binding>,
+    /// Write the session stats to a CSV file.
+    pub export: Vec<Binding>,
+}
+
+/// Actions available in the survival mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SurvivalControls {
+    /// Start a new run after the summary.
+    pub restart: Vec<Binding>,
+}
+
+/// Toggles for the debug visualization layers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DebugControls {
+    pub arc: Vec<Binding>,
+    pub projection: Vec<Binding>,
+    pub reach: Vec<Binding>,
+    pub colliders: Vec<Binding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Palette {
     pub idle: Color,
     pub attack: Color,
     pub defend: Color,
+    pub health: Color,
+    pub stamina: Color,
+    pub charge: Color,
+    /// Tint of the opponents.
+    pub opponent: Color,
+    pub projectile: Color,
+    pub bleed: Color,
+    pub stun: Color,
+    pub slow: Color,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_action_has_bindings() {
+        let mut controls = Controls::default();
+        for &name in Controls::ACTIONS {
+            assert!(controls.action(name).is_some(), "{}", name);
+            assert!(controls.action_mut(name).is_some(), "{}", name);
+        }
+        assert!(controls.action("unknown").is_none());
+    }
+
+    #[test]
+    fn conflicts_list_the_actions_sharing_a_key() {
+        let mut controls = Controls::default();
+        assert!(controls.conflicts().is_empty());
+
+        let key = Binding::Key(geng::Key::R);
+        controls.up.push(key);
+        controls.debug.arc.push(key);
+        assert_eq!(
+            controls.conflicts(),
+            vec![(key, vec!["up", "training.reset", "debug.arc"])]
+        );
+    }
 }
@@ -0,0 +1,49 @@
+use super::*;
+
+/// An input that an action can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(geng::Key),
+    Mouse(geng::MouseButton),
+    /// A button of any connected gamepad.
+    Gamepad(gilrs::Button),
+    /// A gamepad stick or trigger pushed to one side.
+    Axis(gilrs::Axis, AxisSide),
+}
+
+/// Direction of a [Binding::Axis].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AxisSide {
+    Positive,
+    Negative,
+}
+
+/// Press or release of a [Binding], from the window or a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingEvent {
+    Press(Binding),
+    Release(Binding),
+}
+
+impl BindingEvent {
+    /// The key or mouse button press or release of the window event.
+    pub fn from_event(event: &geng::Event) -> Option<Self> {
+        match *event {
+            geng::Event::KeyPress { key } => Some(Self::Press(Binding::Key(key))),
+            geng::Event::KeyRelease { key } => Some(Self::Release(Binding::Key(key))),
+            geng::Event::MousePress { button } => Some(Self::Press(Binding::Mouse(button))),
+            geng::Event::MouseRelease { button } => Some(Self::Release(Binding::Mouse(button))),
+            _ => None,
+        }
+    }
+
+    /// Whether this is the press of one of the bindings.
+    pub fn is_press(&self, bindings: &[Binding]) -> bool {
+        matches!(self, Self::Press(binding) if bindings.contains(binding))
+    }
+
+    /// Whether this is the release of one of the bindings.
+    pub fn is_release(&self, bindings: &[Binding]) -> bool {
+        matches!(self, Self::Release(binding) if bindings.contains(binding))
+    }
+}
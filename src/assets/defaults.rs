@@ -0,0 +1,369 @@
+//! Built-in config values, used for the fields missing from the config files.
+
+use super::*;
+
+use geng::{Key, MouseButton};
+
+fn color(hex: &str) -> Color {
+    Color::try_from(hex.to_owned()).expect("invalid built-in color")
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            resolution: vec2(640, 360),
+            scaling: ScalingMode::Fit,
+        }
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            master_volume: r32(1.0),
+            sfx_volume: r32(0.8),
+            music_volume: r32(0.3),
+        }
+    }
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        Self {
+            fov_min: r32(10.0),
+            fov_max: r32(16.0),
+            fit_margin: r32(3.0),
+            dead_zone: r32(1.0),
+            smoothing: r32(5.0),
+            hit_trauma: r32(0.1),
+            trauma_decay: r32(1.5),
+            shake_offset: r32(0.3),
+            shake_angle: r32(3.0),
+        }
+    }
+}
+
+impl Default for CursorConfig {
+    fn default() -> Self {
+        Self {
+            sensitivity: r32(1.0),
+            trail_time: r32(0.4),
+            fade_time: r32(0.2),
+        }
+    }
+}
+
+impl Default for WeaponConfig {
+    fn default() -> Self {
+        Self {
+            name: "Sword".to_string(),
+            acceleration: r32(50.0),
+            swing_boost: r32(1.5),
+            speed_max: r32(7.0),
+            mass: r32(1.0),
+            power_min: r32(1.0),
+            power_max: r32(5.0),
+            on_hit: vec![
+                EffectConfig {
+                    effect: StatusEffect::Bleed { damage: r32(3.0) },
+                    duration: r32(3.0),
+                    min_power: r32(3.0),
+                },
+                EffectConfig {
+                    effect: StatusEffect::Stun,
+                    duration: r32(0.5),
+                    min_power: r32(4.5),
+                },
+            ],
+        }
+    }
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            walk_speed: r32(3.0),
+            acceleration: r32(10.0),
+            health: r32(100.0),
+            stamina: r32(100.0),
+            stamina_regen: r32(20.0),
+            combo_window: r32(1.5),
+            respawn_time: r32(3.0),
+        }
+    }
+}
+
+impl Default for DashConfig {
+    fn default() -> Self {
+        Self {
+            speed: r32(12.0),
+            duration: r32(0.15),
+            cooldown: r32(0.8),
+            stamina_cost: r32(25.0),
+            invulnerability: r32(0.25),
+        }
+    }
+}
+
+impl Default for CombatConfig {
+    fn default() -> Self {
+        Self {
+            backstab_angle: r32(90.0),
+            backstab_multiplier: r32(2.0),
+            block_angle: r32(120.0),
+            damage_scale: r32(1.5),
+            feint_cost: r32(15.0),
+        }
+    }
+}
+
+impl Default for ClashConfig {
+    fn default() -> Self {
+        Self {
+            restitution: r32(0.6),
+            bind_speed: r32(3.0),
+            disengage: r32(1.0),
+            cooldown: r32(0.2),
+        }
+    }
+}
+
+impl Default for ChargeConfig {
+    fn default() -> Self {
+        Self {
+            curve: ChargeCurve::Linear,
+            full_time: r32(0.4),
+            hold_time: r32(1.0),
+            overcharge_power: r32(0.5),
+        }
+    }
+}
+
+impl Default for ThrowConfig {
+    fn default() -> Self {
+        Self {
+            projectile: ProjectileKind::Dagger,
+            gesture_time: r32(0.1),
+            speed_scale: r32(1.0),
+            speed_min: r32(6.0),
+            speed_max: r32(15.0),
+            cooldown: r32(0.5),
+            stamina_cost: r32(10.0),
+        }
+    }
+}
+
+impl Default for ProjectilesConfig {
+    fn default() -> Self {
+        Self {
+            dagger: ProjectileConfig::default(),
+            arrow: ProjectileConfig {
+                radius: r32(0.05),
+                mass: r32(0.3),
+                lifetime: r32(2.0),
+                length: r32(0.6),
+                on_hit: vec![EffectConfig {
+                    effect: StatusEffect::Slow { factor: r32(0.6) },
+                    duration: r32(2.0),
+                    min_power: R32::ZERO,
+                }],
+            },
+        }
+    }
+}
+
+impl Default for ProjectileConfig {
+    fn default() -> Self {
+        Self {
+            radius: r32(0.1),
+            mass: r32(0.5),
+            lifetime: r32(1.0),
+            length: r32(0.3),
+            on_hit: vec![EffectConfig {
+                effect: StatusEffect::Bleed { damage: r32(2.0) },
+                duration: r32(4.0),
+                min_power: R32::ZERO,
+            }],
+        }
+    }
+}
+
+impl Default for SurvivalConfig {
+    fn default() -> Self {
+        Self {
+            first_wave: 1,
+            wave_growth: 1,
+            wave_delay: r32(3.0),
+            health_growth: r32(0.2),
+            damage_growth: r32(0.1),
+            kill_score: 100,
+            combo_bonus: r32(0.5),
+            high_scores: 10,
+        }
+    }
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            commitment: r32(0.1),
+            guard_range: r32(3.0),
+            jitter: r32(0.2),
+            archetypes: vec![
+                ArchetypeConfig {
+                    name: "Duelist".to_string(),
+                    health: r32(1.0),
+                    damage: r32(1.0),
+                    reaction_time: r32(0.25),
+                    preferred_range: r32(1.8),
+                    charge_time: r32(0.3),
+                    attack_cooldown: r32(1.0),
+                    guard_power: r32(2.0),
+                    guard_cooldown: r32(1.0),
+                    weights: UtilityWeights {
+                        approach: r32(1.0),
+                        strafe: r32(0.6),
+                        attack: r32(1.0),
+                        parry: r32(1.0),
+                        retreat: r32(0.5),
+                    },
+                },
+                ArchetypeConfig {
+                    name: "Brute".to_string(),
+                    health: r32(1.6),
+                    damage: r32(1.4),
+                    reaction_time: r32(0.5),
+                    preferred_range: r32(1.2),
+                    charge_time: r32(0.6),
+                    attack_cooldown: r32(1.5),
+                    guard_power: r32(1.0),
+                    guard_cooldown: r32(2.0),
+                    weights: UtilityWeights {
+                        approach: r32(1.2),
+                        strafe: r32(0.2),
+                        attack: r32(1.2),
+                        parry: r32(0.3),
+                        retreat: r32(0.1),
+                    },
+                },
+                ArchetypeConfig {
+                    name: "Skirmisher".to_string(),
+                    health: r32(0.7),
+                    damage: r32(0.8),
+                    reaction_time: r32(0.15),
+                    preferred_range: r32(2.5),
+                    charge_time: r32(0.2),
+                    attack_cooldown: r32(0.8),
+                    guard_power: r32(2.0),
+                    guard_cooldown: r32(0.8),
+                    weights: UtilityWeights {
+                        approach: r32(0.8),
+                        strafe: r32(1.0),
+                        attack: r32(0.7),
+                        parry: r32(0.8),
+                        retreat: r32(1.0),
+                    },
+                },
+            ],
+        }
+    }
+}
+
+impl Default for MannequinConfig {
+    fn default() -> Self {
+        Self {
+            health: r32(100.0),
+            respawn_time: r32(3.0),
+        }
+    }
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        use gilrs::{Axis, Button};
+        use AxisSide::{Negative, Positive};
+        use Binding::{Axis as A, Gamepad as G, Key as K, Mouse as M};
+        Self {
+            tuning_panel: vec![K(Key::F1)],
+            controls_menu: vec![K(Key::Escape), G(Button::Start)],
+            attack: vec![M(MouseButton::Left), G(Button::RightTrigger2)],
+            defend: vec![M(MouseButton::Right), G(Button::LeftTrigger2)],
+            dash: vec![K(Key::Space), G(Button::South)],
+            feint: vec![K(Key::Q), G(Button::East)],
+            throw: vec![K(Key::E), G(Button::North)],
+            up: vec![
+                K(Key::W),
+                K(Key::ArrowUp),
+                G(Button::DPadUp),
+                A(Axis::LeftStickY, Positive),
+            ],
+            down: vec![
+                K(Key::S),
+                K(Key::ArrowDown),
+                G(Button::DPadDown),
+                A(Axis::LeftStickY, Negative),
+            ],
+            left: vec![
+                K(Key::A),
+                K(Key::ArrowLeft),
+                G(Button::DPadLeft),
+                A(Axis::LeftStickX, Negative),
+            ],
+            right: vec![
+                K(Key::D),
+                K(Key::ArrowRight),
+                G(Button::DPadRight),
+                A(Axis::LeftStickX, Positive),
+            ],
+            debug: DebugControls::default(),
+            training: TrainingControls::default(),
+            survival: SurvivalControls::default(),
+        }
+    }
+}
+
+impl Default for DebugControls {
+    fn default() -> Self {
+        Self {
+            arc: vec![Binding::Key(Key::F2)],
+            projection: vec![Binding::Key(Key::F3)],
+            reach: vec![Binding::Key(Key::F4)],
+            colliders: vec![Binding::Key(Key::F5)],
+        }
+    }
+}
+
+impl Default for TrainingControls {
+    fn default() -> Self {
+        Self {
+            reset: vec![Binding::Key(Key::R)],
+            export: vec![Binding::Key(Key::F6)],
+        }
+    }
+}
+
+impl Default for SurvivalControls {
+    fn default() -> Self {
+        Self {
+            restart: vec![Binding::Key(Key::Enter)],
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            idle: color("#fff"),
+            attack: color("#f00"),
+            defend: color("#00f"),
+            health: color("#d03030"),
+            stamina: color("#40b040"),
+            charge: color("#f0c020"),
+            opponent: color("#ff9090"),
+            projectile: color("#c0c0c0"),
+            bleed: color("#a00000"),
+            stun: color("#ffe040"),
+            slow: color("#60a0ff"),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use super::*;
+
+use serde_json::Value;
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Where an effective config value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Built into the game.
+    Default,
+    /// The config file in the assets directory.
+    Assets,
+    /// The settings file of the user.
+    User,
+    /// A `--set` override on the command line.
+    CommandLine,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Default => "default",
+            Self::Assets => "assets",
+            Self::User => "user settings",
+            Self::CommandLine => "command line",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Sources of the config, merged field by field in order:
+/// built-in defaults, the assets file, the user settings, command line overrides.
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    pub assets: PathBuf,
+    pub user: Option<PathBuf>,
+    /// Overrides in the `path=value` format, e.g. `weapon.speed_max=9`.
+    /// The value is parsed as JSON, falling back to a plain string.
+    pub overrides: Vec<String>,
+}
+
+/// The merged config along with the source of every value.
+#[derive(Debug, Clone)]
+pub struct LayeredConfig {
+    pub config: Config,
+    /// The config without the user settings, falls back to `config` if invalid on its own.
+    pub shipped: Config,
+    /// Source of each leaf value by its path, e.g. `weapon.speed_max`.
+    pub sources: BTreeMap<String, ConfigSource>,
+}
+
+impl ConfigLayers {
+    /// Read, merge and validate all layers.
+    /// On failure, returns a human-readable description of every problem found.
+    pub async fn load(&self) -> Result<LayeredConfig, String> {
+        // The assets file is parsed as a whole since enums in `ron` need the types
+        let assets: Config = load_ron(&self.assets).await?;
+        // Broken user settings should not stop the game, they are skipped instead
+        let settings = self
+            .user
+            .as_ref()
+            .and_then(|path| match UserSettings::load_file(path) {
+                Ok(settings) => Some(settings),
+                Err(err) => {
+                    log::error!("Skipping the user settings: {}", err);
+                    None
+                }
+            });
+
+        let (config, sources) = self.merge(&assets, settings.as_ref())?;
+        let shipped = match settings {
+            Some(_) => self
+                .merge(&assets, None)
+                .map_or_else(|_| config.clone(), |(shipped, _)| shipped),
+            None => config.clone(),
+        };
+        Ok(LayeredConfig {
+            config,
+            shipped,
+            sources,
+        })
+    }
+
+    fn merge(
+        &self,
+        assets: &Config,
+        settings: Option<&UserSettings>,
+    ) -> Result<(Config, BTreeMap<String, ConfigSource>), String> {
+        let mut merge = Merge::new(to_json(&Config::default())?);
+
+        // Values that match the defaults are reported as defaults
+        merge.overlay_changed("", &to_json(assets)?, ConfigSource::Assets);
+
+        if let Some(settings) = settings {
+            let mut with_user = merge.clone();
+            let applied = settings
+                .values()
+                .iter()
+                .try_for_each(|(key, value)| with_user.set(key, value.clone(), ConfigSource::User));
+            match applied {
+                Ok(()) => merge = with_user,
+                Err(err) => log::error!("Skipping the user settings: {}", err),
+            }
+        }
+
+        for assignment in &self.overrides {
+            let (path, value) = assignment
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid override {:?}, expected path=value", assignment))?;
+            let value = serde_json::from_str(value).unwrap_or(Value::String(value.to_owned()));
+            merge.set(path.trim(), value, ConfigSource::CommandLine)?;
+        }
+
+        let config: Config = serde_json::from_value(merge.value)
+            .map_err(|err| format!("Invalid config overrides: {}", err))?;
+
+        let errors = config.validate();
+        if !errors.is_empty() {
+            let mut message = "Invalid config:".to_string();
+            for error in errors {
+                let source = merge
+                    .sources
+                    .get(&error.path)
+                    .copied()
+                    .unwrap_or(ConfigSource::Default);
+                message += &format!("\n  {} (from {})", error, source);
+            }
+            return Err(message);
+        }
+
+        Ok((config, merge.sources))
+    }
+}
+
+impl LayeredConfig {
+    /// Every effective value with its source, one per line.
+    pub fn report(&self) -> String {
+        let value = to_json(&self.config).unwrap_or(Value::Null);
+        let mut report = String::new();
+        for (path, source) in &self.sources {
+            let value = path
+                .split('.')
+                .try_fold(&value, |value, key| value.get(key))
+                .unwrap_or(&Value::Null);
+            report += &format!("{} = {} ({})\n", path, value, source);
+        }
+        report
+    }
+}
+
+fn to_json(config: &Config) -> Result<Value, String> {
+    serde_json::to_value(config).map_err(|err| format!("Failed to serialize config: {}", err))
+}
+
+/// Accumulates the layers on top of each other.
+#[derive(Clone)]
+struct Merge {
+    value: Value,
+    sources: BTreeMap<String, ConfigSource>,
+}
+
+impl Merge {
+    fn new(defaults: Value) -> Self {
+        let mut merge = Self {
+            value: Value::Null,
+            sources: BTreeMap::new(),
+        };
+        merge.mark("", &defaults, ConfigSource::Default);
+        merge.value = defaults;
+        merge
+    }
+
+    /// Record the source of every leaf under the path.
+    fn mark(&mut self, path: &str, value: &Value, source: ConfigSource) {
+        match value {
+            Value::Object(fields) => {
+                for (key, value) in fields {
+                    self.mark(&join(path, key), value, source);
+                }
+            }
+            _ => {
+                self.sources.insert(path.to_owned(), source);
+            }
+        }
+    }
+
+    /// Take the leaves of a complete config that differ from the current ones.
+    fn overlay_changed(&mut self, path: &str, new: &Value, source: ConfigSource) {
+        let current = path
+            .split('.')
+            .filter(|key| !key.is_empty())
+            .try_fold(&self.value, |value, key| value.get(key));
+        match (current, new) {
+            (Some(Value::Object(_)), Value::Object(fields)) => {
+                for (key, value) in fields {
+                    self.overlay_changed(&join(path, key), value, source);
+                }
+            }
+            (Some(current), _) if current == new => {}
+            _ => {
+                // Known paths only, the complete config has the same shape
+                let _ = self.set(path, new.clone(), source);
+            }
+        }
+    }
+
+    /// Replace the value at the path, recursing into objects so that
+    /// only the given fields are overridden.
+    fn set(&mut self, path: &str, new: Value, source: ConfigSource) -> Result<(), String> {
+        let current = path
+            .split('.')
+            .try_fold(&mut self.value, |value, key| value.get_mut(key))
+            .ok_or_else(|| format!("Unknown config field {:?} (from {})", path, source))?;
+        match new {
+            Value::Object(fields) if current.is_object() => {
+                for (key, value) in fields {
+                    self.set(&join(path, &key), value, source)?;
+                }
+            }
+            new => {
+                *current = new.clone();
+                self.mark(path, &new, source);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speed_max(merge: &Merge) -> (Value, ConfigSource) {
+        (
+            merge.value["weapon"]["speed_max"].clone(),
+            merge.sources["weapon.speed_max"],
+        )
+    }
+
+    #[test]
+    fn later_layers_override_earlier_ones() {
+        let defaults = to_json(&Config::default()).unwrap();
+        let mut merge = Merge::new(defaults.clone());
+        assert_eq!(speed_max(&merge).1, ConfigSource::Default);
+
+        let mut assets = defaults;
+        assets["weapon"]["speed_max"] = serde_json::json!(8.0);
+        merge.overlay_changed("", &assets, ConfigSource::Assets);
+        assert_eq!(
+            speed_max(&merge),
+            (serde_json::json!(8.0), ConfigSource::Assets)
+        );
+
+        let user = serde_json::json!({ "speed_max": 9.0 });
+        merge.set("weapon", user, ConfigSource::User).unwrap();
+        assert_eq!(
+            speed_max(&merge),
+            (serde_json::json!(9.0), ConfigSource::User)
+        );
+
+        merge
+            .set(
+                "weapon.speed_max",
+                serde_json::json!(10.0),
+                ConfigSource::CommandLine,
+            )
+            .unwrap();
+        assert_eq!(
+            speed_max(&merge),
+            (serde_json::json!(10.0), ConfigSource::CommandLine)
+        );
+        // Untouched fields of a partially overridden section keep their source
+        assert_eq!(merge.sources["weapon.acceleration"], ConfigSource::Default);
+    }
+
+    #[test]
+    fn assets_equal_to_defaults_are_reported_as_defaults() {
+        let defaults = to_json(&Config::default()).unwrap();
+        let mut merge = Merge::new(defaults.clone());
+        merge.overlay_changed("", &defaults, ConfigSource::Assets);
+        assert!(merge
+            .sources
+            .values()
+            .all(|source| *source == ConfigSource::Default));
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let mut merge = Merge::new(to_json(&Config::default()).unwrap());
+        assert!(merge
+            .set("weapon.speeed", serde_json::json!(1.0), ConfigSource::User)
+            .is_err());
+    }
+
+    #[test]
+    fn only_changed_values_are_saved() {
+        let base = Config::default();
+        let mut config = base.clone();
+        config.weapon.speed_max += r32(1.0);
+
+        let mut settings = UserSettings::default();
+        settings.set_changed(&base, &config).unwrap();
+        let speed_max = to_json(&config).unwrap()["weapon"]["speed_max"].clone();
+        assert_eq!(
+            Value::Object(settings.values().clone()),
+            serde_json::json!({ "weapon": { "speed_max": speed_max } })
+        );
+    }
+}
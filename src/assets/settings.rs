@@ -0,0 +1,94 @@
+use super::*;
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Per-user overrides applied on top of the shipped [Config].
+/// Stored as a partial config in JSON, only the present fields are overridden.
+#[derive(Debug, Clone, Default)]
+pub struct UserSettings {
+    values: serde_json::Map<String, serde_json::Value>,
+}
+
+impl UserSettings {
+    /// Location of the settings file in the user preferences directory.
+    pub fn path() -> PathBuf {
+        preferences::base_path().join("settings.json")
+    }
+
+    /// Read the settings from the file, a missing file results in no overrides.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let source = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {:?}: {}", path, err))?;
+        let values = serde_json::from_str(&source)
+            .map_err(|err| format!("Failed to parse {:?}: {}", path, err))?;
+        Ok(Self { values })
+    }
+
+    /// Load the settings of the current user, a broken file results in no overrides.
+    pub fn load() -> Self {
+        Self::load_file(Self::path()).unwrap_or_else(|err| {
+            log::error!("{}", err);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.values)?)?;
+        Ok(())
+    }
+
+    /// The overridden values as a partial config.
+    pub fn values(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.values
+    }
+
+    /// Override a whole top-level section of the config.
+    pub fn set(&mut self, section: &str, value: &impl Serialize) -> anyhow::Result<()> {
+        self.values
+            .insert(section.to_owned(), serde_json::to_value(value)?);
+        Ok(())
+    }
+
+    /// Override only the values of the config that differ from the base one.
+    pub fn set_changed(&mut self, base: &Config, config: &Config) -> anyhow::Result<()> {
+        let base = serde_json::to_value(base)?;
+        let config = serde_json::to_value(config)?;
+        overlay_changed(&mut self.values, &base, &config);
+        Ok(())
+    }
+}
+
+/// Copy the leaves of `new` that differ from `base` into the partial config.
+fn overlay_changed(values: &mut serde_json::Map<String, Value>, base: &Value, new: &Value) {
+    let (Value::Object(base), Value::Object(new)) = (base, new) else {
+        return;
+    };
+    for (key, new) in new {
+        let base = base.get(key).unwrap_or(&Value::Null);
+        if base == new {
+            continue;
+        }
+        if new.is_object() {
+            let entry = values
+                .entry(key.clone())
+                .or_insert_with(|| Value::Object(Default::default()));
+            if !entry.is_object() {
+                *entry = Value::Object(Default::default());
+            }
+            if let Value::Object(entry) = entry {
+                overlay_changed(entry, base, new);
+            }
+        } else {
+            values.insert(key.clone(), new.clone());
+        }
+    }
+}
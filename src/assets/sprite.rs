@@ -0,0 +1,101 @@
+use super::*;
+
+/// A state of an entity that has its own animation clip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnimationState {
+    Idle,
+    Walk,
+    Windup,
+    Swing,
+    Hurt,
+    Death,
+}
+
+/// A sequence of frames laid out in a single row of the sheet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clip {
+    /// Row of the sheet, counting from the top.
+    pub row: usize,
+    pub frames: usize,
+    /// Frames per second.
+    pub fps: f32,
+    /// Whether to restart after the last frame or hold it.
+    pub looping: bool,
+}
+
+/// Layout of the sprite sheet, loaded from a `ron` file next to the texture.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "SpriteSheet")]
+struct SpriteSheetData {
+    frame_size: vec2<usize>,
+    clips: HashMap<AnimationState, Clip>,
+}
+
+pub struct SpriteSheet {
+    pub texture: ugli::Texture,
+    /// Size of a single frame in pixels.
+    pub frame_size: vec2<usize>,
+    pub clips: HashMap<AnimationState, Clip>,
+}
+
+impl SpriteSheet {
+    /// Get the clip for the state, falling back to [AnimationState::Idle].
+    pub fn clip(&self, state: AnimationState) -> Option<&Clip> {
+        self.clips
+            .get(&state)
+            .or_else(|| self.clips.get(&AnimationState::Idle))
+    }
+
+    /// Returns the texture coordinates of the frame to show
+    /// after the clip has been playing for `time`.
+    pub fn frame_uv(&self, state: AnimationState, time: Time) -> Aabb2<f32> {
+        let (row, frame) = match self.clip(state) {
+            None => (0, 0),
+            Some(clip) => {
+                let frames = clip.frames.max(1);
+                let frame = (time.as_f32().max(0.0) * clip.fps) as usize;
+                let frame = if clip.looping {
+                    frame % frames
+                } else {
+                    frame.min(frames - 1)
+                };
+                (clip.row, frame)
+            }
+        };
+
+        let size = self.texture.size().as_f32();
+        let frame_size = self.frame_size.as_f32() / size;
+        // Texture coordinates go from the bottom
+        let min = vec2(
+            frame as f32 * frame_size.x,
+            1.0 - (row + 1) as f32 * frame_size.y,
+        );
+        Aabb2::point(min).extend_positive(frame_size)
+    }
+}
+
+impl geng::asset::Load for SpriteSheet {
+    type Options = ();
+
+    fn load(
+        manager: &geng::asset::Manager,
+        path: &std::path::Path,
+        _options: &Self::Options,
+    ) -> geng::asset::Future<Self> {
+        let manager = manager.clone();
+        let path = path.to_owned();
+        async move {
+            let data: SpriteSheetData = file::load_detect(&path).await?;
+            let mut texture: ugli::Texture = manager.load(path.with_extension("png")).await?;
+            texture.set_filter(ugli::Filter::Nearest);
+            Ok(Self {
+                texture,
+                frame_size: data.frame_size,
+                clips: data.clips,
+            })
+        }
+        .boxed_local()
+    }
+
+    const DEFAULT_EXT: Option<&'static str> = Some("ron");
+}
@@ -0,0 +1,313 @@
+use super::*;
+
+/// A problem found in the config.
+#[derive(Debug, Clone)]
+pub struct ConfigError {
+    /// Path to the offending field, e.g. `weapon.power_min`.
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Collects errors while walking the config.
+#[derive(Default)]
+struct Validator {
+    errors: Vec<ConfigError>,
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.errors.push(ConfigError {
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn positive(&mut self, path: &str, value: R32) {
+        if value <= R32::ZERO {
+            self.error(path, format!("must be positive, got {}", value));
+        }
+    }
+
+    fn non_negative(&mut self, path: &str, value: R32) {
+        if value < R32::ZERO {
+            self.error(path, format!("must not be negative, got {}", value));
+        }
+    }
+
+    fn fraction(&mut self, path: &str, value: R32) {
+        if !(R32::ZERO..=R32::ONE).contains(&value) {
+            self.error(path, format!("must be in range 0..=1, got {}", value));
+        }
+    }
+
+    fn angle(&mut self, path: &str, value: R32) {
+        if !(R32::ZERO..=r32(360.0)).contains(&value) {
+            self.error(
+                path,
+                format!("must be in range 0..=360 degrees, got {}", value),
+            );
+        }
+    }
+
+    fn effects(&mut self, path: &str, effects: &[EffectConfig]) {
+        for (i, effect) in effects.iter().enumerate() {
+            let path = format!("{}[{}]", path, i);
+            self.non_negative(&format!("{}.duration", path), effect.duration);
+            self.non_negative(&format!("{}.min_power", path), effect.min_power);
+            match effect.effect {
+                StatusEffect::Bleed { damage } => {
+                    self.non_negative(&format!("{}.effect.damage", path), damage);
+                }
+                StatusEffect::Stun => {}
+                StatusEffect::Slow { factor } => {
+                    self.fraction(&format!("{}.effect.factor", path), factor);
+                }
+            }
+        }
+    }
+
+    fn weapon(&mut self, path: &str, weapon: &WeaponConfig) {
+        let field = |name: &str| format!("{}.{}", path, name);
+        self.positive(&field("acceleration"), weapon.acceleration);
+        self.positive(&field("swing_boost"), weapon.swing_boost);
+        self.positive(&field("speed_max"), weapon.speed_max);
+        self.positive(&field("mass"), weapon.mass);
+        self.non_negative(&field("power_min"), weapon.power_min);
+        if weapon.power_min > weapon.power_max {
+            self.error(
+                &field("power_min"),
+                format!(
+                    "must not exceed {} ({} > {})",
+                    field("power_max"),
+                    weapon.power_min,
+                    weapon.power_max
+                ),
+            );
+        }
+        self.effects(&field("on_hit"), &weapon.on_hit);
+    }
+
+    fn bound(&mut self, path: &str, keys: &[Binding]) {
+        if keys.is_empty() {
+            self.error(path, "must have at least one key bound");
+        }
+    }
+}
+
+impl Config {
+    /// Check the values for consistency.
+    /// Returns an empty list if the config is valid.
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut v = Validator::default();
+
+        let display = &self.display;
+        if display.resolution.x == 0 || display.resolution.y == 0 {
+            v.error(
+                "display.resolution",
+                format!(
+                    "must be non-zero, got {}x{}",
+                    display.resolution.x, display.resolution.y
+                ),
+            );
+        }
+
+        let audio = &self.audio;
+        v.fraction("audio.master_volume", audio.master_volume);
+        v.fraction("audio.sfx_volume", audio.sfx_volume);
+        v.fraction("audio.music_volume", audio.music_volume);
+
+        let camera = &self.camera;
+        v.positive("camera.fov_min", camera.fov_min);
+        if camera.fov_min > camera.fov_max {
+            v.error(
+                "camera.fov_min",
+                format!(
+                    "must not exceed camera.fov_max ({} > {})",
+                    camera.fov_min, camera.fov_max
+                ),
+            );
+        }
+        v.non_negative("camera.fit_margin", camera.fit_margin);
+        v.non_negative("camera.dead_zone", camera.dead_zone);
+        v.positive("camera.smoothing", camera.smoothing);
+        v.non_negative("camera.hit_trauma", camera.hit_trauma);
+        v.non_negative("camera.trauma_decay", camera.trauma_decay);
+        v.non_negative("camera.shake_offset", camera.shake_offset);
+        v.non_negative("camera.shake_angle", camera.shake_angle);
+
+        let cursor = &self.cursor;
+        v.positive("cursor.sensitivity", cursor.sensitivity);
+        v.positive("cursor.trail_time", cursor.trail_time);
+        v.non_negative("cursor.fade_time", cursor.fade_time);
+        if cursor.fade_time >= cursor.trail_time {
+            v.error(
+                "cursor.fade_time",
+                format!(
+                    "must be less than cursor.trail_time ({} >= {})",
+                    cursor.fade_time, cursor.trail_time
+                ),
+            );
+        }
+
+        v.weapon("weapon", &self.weapon);
+        for (i, weapon) in self.arsenal.iter().enumerate() {
+            let path = format!("arsenal[{}]", i);
+            v.weapon(&path, weapon);
+            let taken = std::iter::once(&self.weapon)
+                .chain(&self.arsenal[..i])
+                .any(|other| other.name == weapon.name);
+            if taken {
+                v.error(
+                    &format!("{}.name", path),
+                    format!("duplicate weapon {:?}", weapon.name),
+                );
+            }
+        }
+
+        let player = &self.player;
+        v.non_negative("player.walk_speed", player.walk_speed);
+        v.positive("player.acceleration", player.acceleration);
+        v.positive("player.health", player.health);
+        v.positive("player.stamina", player.stamina);
+        v.non_negative("player.stamina_regen", player.stamina_regen);
+        v.non_negative("player.combo_window", player.combo_window);
+        v.non_negative("player.respawn_time", player.respawn_time);
+
+        let dash = &self.dash;
+        v.non_negative("dash.speed", dash.speed);
+        v.non_negative("dash.duration", dash.duration);
+        v.non_negative("dash.cooldown", dash.cooldown);
+        v.non_negative("dash.stamina_cost", dash.stamina_cost);
+        v.non_negative("dash.invulnerability", dash.invulnerability);
+
+        let combat = &self.combat;
+        v.angle("combat.backstab_angle", combat.backstab_angle);
+        v.non_negative("combat.backstab_multiplier", combat.backstab_multiplier);
+        v.angle("combat.block_angle", combat.block_angle);
+        v.non_negative("combat.damage_scale", combat.damage_scale);
+        v.non_negative("combat.feint_cost", combat.feint_cost);
+
+        let clash = &self.clash;
+        v.fraction("clash.restitution", clash.restitution);
+        v.non_negative("clash.bind_speed", clash.bind_speed);
+        v.positive("clash.disengage", clash.disengage);
+        v.non_negative("clash.cooldown", clash.cooldown);
+
+        let charge = &self.charge;
+        v.positive("charge.full_time", charge.full_time);
+        v.non_negative("charge.hold_time", charge.hold_time);
+        v.fraction("charge.overcharge_power", charge.overcharge_power);
+
+        let throw = &self.throw;
+        v.positive("throw.gesture_time", throw.gesture_time);
+        v.non_negative("throw.speed_scale", throw.speed_scale);
+        v.positive("throw.speed_min", throw.speed_min);
+        if throw.speed_min > throw.speed_max {
+            v.error(
+                "throw.speed_min",
+                format!(
+                    "must not exceed throw.speed_max ({} > {})",
+                    throw.speed_min, throw.speed_max
+                ),
+            );
+        }
+        v.non_negative("throw.cooldown", throw.cooldown);
+        v.non_negative("throw.stamina_cost", throw.stamina_cost);
+
+        let projectiles = &self.projectiles;
+        for (name, projectile) in [
+            ("dagger", &projectiles.dagger),
+            ("arrow", &projectiles.arrow),
+        ] {
+            let path = |field: &str| format!("projectiles.{}.{}", name, field);
+            v.positive(&path("radius"), projectile.radius);
+            v.positive(&path("mass"), projectile.mass);
+            v.positive(&path("lifetime"), projectile.lifetime);
+            v.non_negative(&path("length"), projectile.length);
+            v.effects(&path("on_hit"), &projectile.on_hit);
+        }
+
+        let survival = &self.survival;
+        if survival.first_wave == 0 {
+            v.error("survival.first_wave", "must be at least 1");
+        }
+        v.non_negative("survival.wave_delay", survival.wave_delay);
+        v.non_negative("survival.health_growth", survival.health_growth);
+        v.non_negative("survival.damage_growth", survival.damage_growth);
+        v.non_negative("survival.combo_bonus", survival.combo_bonus);
+
+        let ai = &self.ai;
+        v.non_negative("ai.commitment", ai.commitment);
+        v.non_negative("ai.guard_range", ai.guard_range);
+        v.fraction("ai.jitter", ai.jitter);
+        if ai.archetypes.is_empty() {
+            v.error("ai.archetypes", "must have at least one archetype");
+        }
+        for (i, archetype) in ai.archetypes.iter().enumerate() {
+            let path = |field: &str| format!("ai.archetypes[{}].{}", i, field);
+            if ai.archetypes[..i]
+                .iter()
+                .any(|other| other.name == archetype.name)
+            {
+                v.error(
+                    &path("name"),
+                    format!("duplicate archetype {:?}", archetype.name),
+                );
+            }
+            v.positive(&path("health"), archetype.health);
+            v.non_negative(&path("damage"), archetype.damage);
+            v.non_negative(&path("reaction_time"), archetype.reaction_time);
+            v.non_negative(&path("preferred_range"), archetype.preferred_range);
+            v.non_negative(&path("charge_time"), archetype.charge_time);
+            v.non_negative(&path("attack_cooldown"), archetype.attack_cooldown);
+            v.non_negative(&path("guard_power"), archetype.guard_power);
+            v.non_negative(&path("guard_cooldown"), archetype.guard_cooldown);
+            let weights = &archetype.weights;
+            for (name, weight) in [
+                ("approach", weights.approach),
+                ("strafe", weights.strafe),
+                ("attack", weights.attack),
+                ("parry", weights.parry),
+                ("retreat", weights.retreat),
+            ] {
+                v.non_negative(&path(&format!("weights.{}", name)), weight);
+            }
+        }
+
+        let mannequin = &self.mannequin;
+        v.positive("mannequin.health", mannequin.health);
+        v.non_negative("mannequin.respawn_time", mannequin.respawn_time);
+
+        let controls = &self.controls;
+        v.bound("controls.attack", &controls.attack);
+        v.bound("controls.defend", &controls.defend);
+        v.bound("controls.dash", &controls.dash);
+        v.bound("controls.throw", &controls.throw);
+        v.bound("controls.controls_menu", &controls.controls_menu);
+
+        v.errors
+    }
+}
+
+impl Level {
+    /// Check the references to the config.
+    /// Returns an empty list if the level is valid.
+    pub fn validate(&self, config: &Config) -> Vec<ConfigError> {
+        let mut v = Validator::default();
+        for (i, spawn) in self.opponents.iter().enumerate() {
+            if config.ai.archetype(&spawn.archetype).is_none() {
+                v.error(
+                    &format!("level.opponents[{}].archetype", i),
+                    format!("unknown archetype {:?}", spawn.archetype),
+                );
+            }
+        }
+        v.errors
+    }
+}
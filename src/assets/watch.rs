@@ -0,0 +1,56 @@
+use super::*;
+
+use std::time::SystemTime;
+
+/// Polls the config files for modifications and re-merges the layers on change.
+pub struct ConfigWatcher {
+    layers: ConfigLayers,
+    /// Modification times of the last seen versions of the assets and the user files.
+    modified: [Option<SystemTime>; 2],
+    /// Time until the next check.
+    next_check: f64,
+}
+
+impl ConfigWatcher {
+    /// How often (in seconds) the files are checked for modifications.
+    const CHECK_PERIOD: f64 = 0.5;
+
+    pub fn new(layers: ConfigLayers) -> Self {
+        Self {
+            modified: Self::modified_times(&layers),
+            layers,
+            next_check: Self::CHECK_PERIOD,
+        }
+    }
+
+    fn modified_times(layers: &ConfigLayers) -> [Option<SystemTime>; 2] {
+        let modified = |path: &std::path::Path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        };
+        [
+            modified(&layers.assets),
+            layers.user.as_deref().and_then(modified),
+        ]
+    }
+
+    /// Returns the freshly merged config if any of the files has changed since the last check.
+    pub fn update(&mut self, delta_time: f64) -> Option<Result<LayeredConfig, String>> {
+        self.next_check -= delta_time;
+        if self.next_check > 0.0 {
+            return None;
+        }
+        self.next_check = Self::CHECK_PERIOD;
+
+        let modified = Self::modified_times(&self.layers);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        log::info!("Reloading config from {:?}", self.layers.assets);
+        // The files are local since their modification times are known
+        Some(futures::executor::block_on(self.layers.load()))
+    }
+}
@@ -0,0 +1,145 @@
+use crate::prelude::*;
+
+/// Plays sound effects and music in response to the model.
+pub struct GameAudio {
+    geng: Geng,
+    assets: Rc<Assets>,
+    music: geng::SoundEffect,
+    /// Time until the next footstep.
+    footstep_timer: f32,
+}
+
+impl GameAudio {
+    /// Distance at which sounds start getting quieter.
+    const REF_DISTANCE: f64 = 5.0;
+    /// Distance at which sounds stop getting quieter.
+    const MAX_DISTANCE: f64 = 30.0;
+    /// Distance of the listener above the scene.
+    const LISTENER_HEIGHT: f64 = 5.0;
+
+    pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+        let mut music = assets.sounds.music.effect();
+        music.play();
+
+        // Looking down at the scene
+        geng.audio()
+            .set_listener_orientation(vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0));
+
+        Self {
+            geng: geng.clone(),
+            assets: assets.clone(),
+            music,
+            footstep_timer: 0.0,
+        }
+    }
+
+    pub fn handle_outcome(&mut self, model: &Model, outcome: &Outcome) {
+        match outcome {
+            Outcome::SwingStarted {
+                position, speed, ..
+            } => {
+                // Faster swings are louder and higher-pitched
+                let t = (*speed / model.config.weapon.speed_max)
+                    .as_f32()
+                    .clamp(0.0, 1.0);
+                self.play(
+                    model,
+                    &self.assets.sounds.swing,
+                    *position,
+                    0.3 + 0.7 * t,
+                    0.8 + 0.5 * t,
+                );
+            }
+            Outcome::Hit { position, .. } => {
+                let pitch = thread_rng().gen_range(0.9..=1.1);
+                self.play(model, &self.assets.sounds.hit, *position, 1.0, pitch);
+            }
+            Outcome::Parried { position, .. } => {
+                let pitch = thread_rng().gen_range(0.9..=1.1);
+                self.play(model, &self.assets.sounds.parry, *position, 1.0, pitch);
+            }
+            Outcome::Clash { position, .. } => {
+                let pitch = thread_rng().gen_range(0.7..=0.8);
+                self.play(model, &self.assets.sounds.parry, *position, 1.0, pitch);
+            }
+            Outcome::Feinted { position } => {
+                // An aborted whoosh
+                self.play(model, &self.assets.sounds.swing, *position, 0.5, 0.6);
+            }
+            Outcome::Thrown { position, velocity } => {
+                // Higher-pitched than the swings, rising with the speed
+                let t = (velocity.len() / model.config.throw.speed_max)
+                    .as_f32()
+                    .clamp(0.0, 1.0);
+                self.play(
+                    model,
+                    &self.assets.sounds.swing,
+                    *position,
+                    0.5 + 0.5 * t,
+                    1.2 + 0.4 * t,
+                );
+            }
+            Outcome::WallHit { position, .. } => {
+                let pitch = thread_rng().gen_range(1.2..=1.4);
+                self.play(model, &self.assets.sounds.parry, *position, 0.5, pitch);
+            }
+            Outcome::Dashed { position, .. } => {
+                self.play(model, &self.assets.sounds.footstep, *position, 1.0, 0.7);
+            }
+            Outcome::Killed { position, .. } => {
+                self.play(model, &self.assets.sounds.death, *position, 1.0, 1.0);
+            }
+            Outcome::ComboStep { .. } | Outcome::EffectApplied { .. } => {}
+        }
+    }
+
+    pub fn update(&mut self, model: &Model, delta_time: f32) {
+        let config = &model.config.audio;
+        self.geng
+            .audio()
+            .set_volume(config.master_volume.as_f32() as f64);
+        self.music.set_volume(config.music_volume.as_f32() as f64);
+
+        // Hear from the camera
+        let listener = model.camera.center.map(|x| x as f64);
+        self.geng.audio().set_listener_position(vec3(
+            listener.x,
+            listener.y,
+            Self::LISTENER_HEIGHT,
+        ));
+
+        // Footsteps
+        let player = &model.player;
+        self.footstep_timer -= delta_time;
+        if self.footstep_timer <= 0.0 && player.velocity.len().as_f32() > 1.0 {
+            self.footstep_timer = 0.3;
+            let pitch = thread_rng().gen_range(0.85..=1.15);
+            self.play(
+                model,
+                &self.assets.sounds.footstep,
+                player.position,
+                0.4,
+                pitch,
+            );
+        }
+    }
+
+    /// Play the sound at the world position.
+    fn play(
+        &self,
+        model: &Model,
+        sound: &geng::Sound,
+        position: vec2<Coord>,
+        volume: f32,
+        speed: f32,
+    ) {
+        let mut effect = sound.effect();
+        let position = position.as_f32().map(|x| x as f64);
+        effect.set_position(vec3(position.x, position.y, 0.0));
+        effect.set_ref_distance(Self::REF_DISTANCE);
+        effect.set_max_distance(Self::MAX_DISTANCE);
+        effect.set_volume((model.config.audio.sfx_volume.as_f32() * volume) as f64);
+        effect.set_speed(speed as f64);
+        effect.play();
+    }
+}
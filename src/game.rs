@@ -1,54 +1,279 @@
-use crate::{prelude::*, render::GameRender};
+mod controls;
+mod gamepad;
+mod survival;
+mod training;
+mod tuning;
+
+use self::{
+    controls::ControlsMenu, gamepad::GamepadInput, survival::SurvivalSession,
+    training::TrainingSession, tuning::TuningPanel,
+};
+
+use crate::{
+    audio::GameAudio,
+    prelude::*,
+    render::{GameRender, UtilRender},
+};
 
 pub struct State {
-    // geng: Geng,
+    geng: Geng,
     // assets: Rc<Assets>,
     render: GameRender,
+    audio: GameAudio,
+    util_render: UtilRender,
+    gamepad: GamepadInput,
+
+    config_watcher: ConfigWatcher,
+    /// Error from the last attempt to reload the config.
+    config_error: Option<String>,
+    tuning: TuningPanel,
+    controls_menu: ControlsMenu,
+    /// Stats of the training mode, if enabled.
+    training: Option<TrainingSession>,
+    /// Waves and score of the survival mode, if enabled.
+    survival: Option<SurvivalSession>,
 
     pub model: Model,
 }
 
 impl State {
-    pub fn new(geng: &Geng, assets: &Rc<Assets>, config: Config) -> Self {
+    pub fn new(
+        geng: &Geng,
+        assets: &Rc<Assets>,
+        layered: LayeredConfig,
+        level: Level,
+        config_watcher: ConfigWatcher,
+    ) -> Self {
         geng.window().lock_cursor();
 
         Self {
-            // geng: geng.clone(),
+            geng: geng.clone(),
             // assets: assets.clone(),
             render: GameRender::new(geng, assets),
+            audio: GameAudio::new(geng, assets),
+            util_render: UtilRender::new(geng, assets),
+            gamepad: GamepadInput::default(),
 
-            model: Model::new(geng, config),
+            config_watcher,
+            config_error: None,
+            tuning: TuningPanel::new(layered.config.clone()),
+            controls_menu: ControlsMenu::new(layered.shipped.controls),
+            training: None,
+            survival: None,
+
+            model: Model::new(layered.config, level),
         }
     }
-}
 
-impl geng::State for State {
-    fn update(&mut self, delta_time: f64) {
-        let delta_time = r32(delta_time as f32);
-        self.model.update(delta_time);
+    /// The cursor is free while any menu is open.
+    fn update_cursor_lock(&self) {
+        if self.tuning.open || self.controls_menu.open {
+            self.geng.window().unlock_cursor();
+        } else {
+            self.geng.window().lock_cursor();
+        }
     }
 
-    fn handle_event(&mut self, event: geng::Event) {
+    /// Whether any of the bindings is held down.
+    fn is_pressed(&self, bindings: &[Binding]) -> bool {
+        let window = self.geng.window();
+        bindings.iter().any(|&binding| match binding {
+            Binding::Key(key) => window.is_key_pressed(key),
+            Binding::Mouse(button) => window.is_button_pressed(button),
+            Binding::Gamepad(_) | Binding::Axis(..) => self.gamepad.is_held(binding),
+        })
+    }
+
+    /// Inputs of the player held down on the keyboard, the mouse and the gamepads.
+    fn read_input(&self) -> PlayerInput {
+        let controls = &self.model.config.controls;
+        let pressed = |bindings| self.is_pressed(bindings);
+        let axis = |positive, negative| match (pressed(positive), pressed(negative)) {
+            (true, false) => Coord::ONE,
+            (false, true) => -Coord::ONE,
+            _ => Coord::ZERO,
+        };
+        PlayerInput {
+            attack: pressed(&controls.attack),
+            defend: pressed(&controls.defend),
+            move_dir: vec2(
+                axis(&controls.right, &controls.left),
+                axis(&controls.up, &controls.down),
+            ),
+        }
+    }
+
+    /// Enable the training mode, recording the session stats.
+    pub fn start_training(&mut self) {
+        self.model.round_time = Time::ZERO;
+        self.training = Some(TrainingSession::new(&self.geng, &self.model));
+    }
+
+    /// Enable the survival mode, starting a new run.
+    pub fn start_survival(&mut self) {
+        self.survival = Some(SurvivalSession::new(&self.geng, &mut self.model));
+    }
+
+    /// Start over with a fresh model in the survival mode.
+    fn restart_survival(&mut self) {
+        self.model = Model::new(self.model.config.clone(), self.model.level.clone());
+        self.start_survival();
+        if let Some(training) = &mut self.training {
+            training.reset(&self.model);
+        }
+    }
+
+    /// React to the press or release of a key, a mouse or a gamepad button.
+    fn handle_binding(&mut self, event: BindingEvent) {
+        if self
+            .controls_menu
+            .handle_event(&event, &mut self.model.config.controls)
+        {
+            return;
+        }
+
+        let controls = &self.model.config.controls;
+        if event.is_press(&controls.tuning_panel) {
+            self.tuning.open = !self.tuning.open;
+            self.update_cursor_lock();
+        } else if event.is_press(&controls.controls_menu) {
+            self.controls_menu.open = !self.controls_menu.open;
+            self.update_cursor_lock();
+        }
+        if let Some(training) = &mut self.training {
+            let controls = &self.model.config.controls.training;
+            if event.is_press(&controls.reset) {
+                self.model.round_time = Time::ZERO;
+                training.reset(&self.model);
+            } else if event.is_press(&controls.export) {
+                let time = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |time| time.as_secs());
+                training.export(run_dir().join(format!("training-{}.csv", time)));
+            }
+        }
+        if let Some(survival) = &self.survival {
+            let controls = &self.model.config.controls.survival;
+            if survival.is_over() && event.is_press(&controls.restart) {
+                self.restart_survival();
+            }
+        }
+        let debug = &self.model.config.controls.debug;
+        let layers = &mut self.render.debug_layers;
+        for (keys, layer) in [
+            (&debug.arc, &mut layers.arc),
+            (&debug.projection, &mut layers.projection),
+            (&debug.reach, &mut layers.reach),
+            (&debug.colliders, &mut layers.colliders),
+        ] {
+            if event.is_press(keys) {
+                *layer = !*layer;
+            }
+        }
+
+        if self.tuning.open || self.controls_menu.open {
+            // Mouse is used by the menus
+            if let BindingEvent::Press(Binding::Mouse(_))
+            | BindingEvent::Release(Binding::Mouse(_)) = event
+            {
+                return;
+            }
+        }
+
+        if event.is_press(&self.model.config.controls.dash) {
+            self.model.handle_event(Event::Dash);
+        }
+        if event.is_press(&self.model.config.controls.feint) {
+            self.model.handle_event(Event::Feint);
+        }
+        if event.is_press(&self.model.config.controls.throw) {
+            self.model.handle_event(Event::Throw);
+        }
+
         // Cursor state
-        if geng_utils::key::is_event_press(&event, &self.model.config.controls.attack) {
+        if event.is_press(&self.model.config.controls.attack) {
             self.model.handle_event(Event::Charge(WeaponIntent::Attack));
-        } else if geng_utils::key::is_event_release(&event, &self.model.config.controls.attack) {
+        } else if event.is_release(&self.model.config.controls.attack) {
             if let CursorState::Attack = self.model.player.cursor.state {
                 self.model.handle_event(Event::Release);
             }
-        } else if geng_utils::key::is_event_press(&event, &self.model.config.controls.defend) {
+        } else if event.is_press(&self.model.config.controls.defend) {
             self.model.handle_event(Event::Charge(WeaponIntent::Defend));
-        } else if geng_utils::key::is_event_release(&event, &self.model.config.controls.defend) {
+        } else if event.is_release(&self.model.config.controls.defend) {
             if let CursorState::Defend = self.model.player.cursor.state {
                 self.model.handle_event(Event::Release);
             }
         }
+    }
+}
+
+impl geng::State for State {
+    fn update(&mut self, delta_time: f64) {
+        if let Some(result) = self.config_watcher.update(delta_time) {
+            match result {
+                Ok(layered) => {
+                    self.controls_menu.set_shipped(layered.shipped.controls);
+                    self.tuning.set_loaded(layered.config.clone());
+                    self.model.set_config(layered.config);
+                    self.config_error = None;
+                }
+                Err(err) => {
+                    log::error!("{}", err);
+                    self.config_error = Some(err);
+                }
+            }
+        }
+
+        for event in self.gamepad.poll(&self.geng) {
+            self.handle_binding(event);
+        }
+
+        if self.tuning.open {
+            self.model.apply_config();
+        }
+
+        let delta_time = r32(delta_time as f32);
+        // Frozen on the summary of a finished survival run
+        if !self.survival.as_ref().is_some_and(SurvivalSession::is_over) {
+            self.model.input = self.read_input();
+            self.model.update(delta_time);
+        }
+
+        for outcome in std::mem::take(&mut self.model.outcomes) {
+            self.render.handle_outcome(&outcome);
+            self.audio.handle_outcome(&self.model, &outcome);
+            if let Some(training) = &mut self.training {
+                training.handle_outcome(&self.model, &outcome);
+            }
+            if let Some(survival) = &mut self.survival {
+                survival.handle_outcome(&self.model, &outcome);
+            }
+        }
+        if let Some(training) = &mut self.training {
+            training.update(&self.model);
+        }
+        if let Some(survival) = &mut self.survival {
+            survival.update(&mut self.model, delta_time);
+        }
+        self.render.update(&self.model, delta_time.as_f32());
+        self.audio.update(&self.model, delta_time.as_f32());
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        if let Some(event) = BindingEvent::from_event(&event) {
+            self.handle_binding(event);
+        }
 
         if let geng::Event::RawMouseMove { delta } = event {
-            let delta = delta.as_r32();
+            // Mouse is used by the menus
+            if self.tuning.open || self.controls_menu.open {
+                return;
+            }
             // Convert to world coordinates
-            let delta = delta
-                * r32(self.model.camera.fov / self.render.framebuffer_size.y as f32)
+            let delta = self
+                .render
+                .screen_to_world_delta(delta, &self.model.camera)
+                .as_r32()
                 * self.model.config.cursor.sensitivity;
             self.model.handle_event(Event::CursorMove { delta });
         }
@@ -57,5 +282,22 @@ impl geng::State for State {
     fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
         ugli::clear(framebuffer, Some(Color::BLACK), None, None);
         self.render.draw(&self.model, framebuffer);
+        if let Some(training) = &self.training {
+            training.draw(&self.model, framebuffer);
+        }
+        if let Some(survival) = &self.survival {
+            survival.draw(&self.model, framebuffer);
+        }
+        if let Some(error) = &self.config_error {
+            self.util_render.draw_error(error, framebuffer);
+        }
+    }
+
+    fn ui<'a>(&'a mut self, cx: &'a geng::ui::Controller) -> Box<dyn geng::ui::Widget + 'a> {
+        // The controls menu covers the tuning panel
+        if self.controls_menu.open {
+            return self.controls_menu.ui(cx, &mut self.model.config.controls);
+        }
+        self.tuning.ui(cx, &mut self.model)
     }
 }
@@ -0,0 +1,157 @@
+use crate::prelude::*;
+
+use geng::ui::{self, *};
+
+const FONT_SIZE: f32 = 16.0;
+
+/// How the next input changes the bindings of an action.
+#[derive(Debug, Clone, Copy)]
+enum ListenMode {
+    /// Replace all bindings with the new one.
+    Replace,
+    /// Add the new binding to the existing ones.
+    Add,
+}
+
+/// Menu for rebinding the controls, saved to the [UserSettings].
+pub struct ControlsMenu {
+    pub open: bool,
+    /// The action waiting for the next input.
+    listening: Option<(&'static str, ListenMode)>,
+    /// Controls from the shipped config, used for resetting.
+    shipped: Controls,
+    /// Message about the result of the last save.
+    status: Option<String>,
+}
+
+impl ControlsMenu {
+    pub fn new(shipped: Controls) -> Self {
+        Self {
+            open: false,
+            listening: None,
+            shipped,
+            status: None,
+        }
+    }
+
+    /// Update the controls used for resetting after the config is reloaded.
+    pub fn set_shipped(&mut self, shipped: Controls) {
+        self.shipped = shipped;
+    }
+
+    /// Bind the pressed key, mouse or gamepad button to the action being listened for,
+    /// Escape cancels the listening.
+    /// Returns `true` if the event was consumed.
+    pub fn handle_event(&mut self, event: &BindingEvent, controls: &mut Controls) -> bool {
+        let Some((name, mode)) = self.listening else {
+            return false;
+        };
+        let key = match *event {
+            BindingEvent::Press(Binding::Key(geng::Key::Escape)) => {
+                self.listening = None;
+                return true;
+            }
+            BindingEvent::Press(binding) => binding,
+            BindingEvent::Release(_) => return false,
+        };
+        if let Some(keys) = controls.action_mut(name) {
+            match mode {
+                ListenMode::Replace => *keys = vec![key],
+                ListenMode::Add => {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+        self.listening = None;
+        true
+    }
+
+    pub fn ui<'a>(
+        &'a mut self,
+        cx: &'a ui::Controller,
+        controls: &'a mut Controls,
+    ) -> Box<dyn ui::Widget + 'a> {
+        if !self.open {
+            return Box::new(ui::Void);
+        }
+
+        let font = cx.theme().font.clone();
+        let text = |text: String, color: Color| ui::Text::new(text, font.clone(), FONT_SIZE, color);
+
+        let mut rows = ui::column![text("Controls".to_string(), Color::WHITE)];
+        for &name in Controls::ACTIONS {
+            let keys = controls.action(name).map_or(&[][..], |keys| keys);
+            let bindings = if self.listening.is_some_and(|(action, _)| action == name) {
+                "press a key, a button or a stick, Escape to cancel...".to_string()
+            } else {
+                keys.iter().map(key_name).collect::<Vec<_>>().join(", ")
+            };
+
+            let change = ui::Button::new(cx, "Change");
+            if change.was_clicked() {
+                self.listening = Some((name, ListenMode::Replace));
+            }
+            let add = ui::Button::new(cx, "Add");
+            if add.was_clicked() {
+                self.listening = Some((name, ListenMode::Add));
+            }
+            rows.push(Box::new(ui::row![
+                text(name.to_string(), Color::WHITE).fixed_size(vec2(150.0, FONT_SIZE as f64)),
+                text(bindings, Color::WHITE).fixed_size(vec2(250.0, FONT_SIZE as f64)),
+                change.padding_horizontal(4.0),
+                add.padding_horizontal(4.0),
+            ]));
+        }
+
+        for (key, names) in controls.conflicts() {
+            rows.push(Box::new(text(
+                format!("{} is bound to {}", key_name(&key), names.join(", ")),
+                Color::RED,
+            )));
+        }
+
+        let save = ui::Button::new(cx, "Save");
+        if save.was_clicked() {
+            let mut settings = UserSettings::load();
+            let result = settings
+                .set("controls", controls)
+                .and_then(|()| settings.save());
+            self.status = Some(match result {
+                Ok(()) => format!("Saved to {:?}", UserSettings::path()),
+                Err(err) => format!("Failed to save: {}", err),
+            });
+        }
+        let reset = ui::Button::new(cx, "Reset");
+        if reset.was_clicked() {
+            *controls = self.shipped.clone();
+            self.listening = None;
+        }
+        rows.push(Box::new(ui::row![
+            save.padding_horizontal(4.0),
+            reset.padding_horizontal(4.0)
+        ]));
+        if let Some(status) = &self.status {
+            rows.push(Box::new(text(status.clone(), Color::WHITE)));
+        }
+
+        Box::new(
+            ui::stack![
+                ui::ColorBox::new(Color::new(0.0, 0.0, 0.0, 0.7)),
+                rows.uniform_padding(8.0)
+            ]
+            .align(vec2(0.5, 0.5)),
+        )
+    }
+}
+
+pub(super) fn key_name(key: &Binding) -> String {
+    match key {
+        Binding::Key(key) => format!("{:?}", key),
+        Binding::Mouse(button) => format!("Mouse {:?}", button),
+        Binding::Gamepad(button) => format!("Pad {:?}", button),
+        Binding::Axis(axis, AxisSide::Positive) => format!("Pad {:?}+", axis),
+        Binding::Axis(axis, AxisSide::Negative) => format!("Pad {:?}-", axis),
+    }
+}
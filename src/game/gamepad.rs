@@ -0,0 +1,123 @@
+use crate::prelude::*;
+
+use gilrs::{Axis, Button};
+
+/// How far an axis has to be pushed to count as pressed.
+const AXIS_THRESHOLD: f32 = 0.5;
+
+const BUTTONS: [Button; 19] = [
+    Button::South,
+    Button::East,
+    Button::North,
+    Button::West,
+    Button::C,
+    Button::Z,
+    Button::LeftTrigger,
+    Button::LeftTrigger2,
+    Button::RightTrigger,
+    Button::RightTrigger2,
+    Button::Select,
+    Button::Start,
+    Button::Mode,
+    Button::LeftThumb,
+    Button::RightThumb,
+    Button::DPadUp,
+    Button::DPadDown,
+    Button::DPadLeft,
+    Button::DPadRight,
+];
+
+const AXES: [Axis; 8] = [
+    Axis::LeftStickX,
+    Axis::LeftStickY,
+    Axis::LeftZ,
+    Axis::RightStickX,
+    Axis::RightStickY,
+    Axis::RightZ,
+    Axis::DPadX,
+    Axis::DPadY,
+];
+
+/// Turns the state of the gamepads into [BindingEvent]s.
+/// Geng keeps the gamepad state up to date, but does not report the events.
+#[derive(Debug, Default)]
+pub struct GamepadInput {
+    /// Bindings held on any of the gamepads as of the last poll.
+    held: Vec<Binding>,
+}
+
+impl GamepadInput {
+    /// Read the connected gamepads, returns the presses and releases since the last poll.
+    pub fn poll(&mut self, geng: &Geng) -> Vec<BindingEvent> {
+        let mut held = Vec::new();
+        if let Some(gilrs) = geng.gilrs() {
+            for (_, gamepad) in gilrs.gamepads() {
+                let buttons = BUTTONS
+                    .into_iter()
+                    .filter(|&button| gamepad.is_pressed(button))
+                    .map(Binding::Gamepad);
+                let axes = AXES.into_iter().filter_map(|axis| {
+                    let value = gamepad.value(axis);
+                    if value >= AXIS_THRESHOLD {
+                        Some(Binding::Axis(axis, AxisSide::Positive))
+                    } else if value <= -AXIS_THRESHOLD {
+                        Some(Binding::Axis(axis, AxisSide::Negative))
+                    } else {
+                        None
+                    }
+                });
+                held.extend(buttons.chain(axes));
+            }
+        }
+        self.update(held)
+    }
+
+    /// Whether the binding is held on any of the gamepads.
+    pub fn is_held(&self, binding: Binding) -> bool {
+        self.held.contains(&binding)
+    }
+
+    fn update(&mut self, held: Vec<Binding>) -> Vec<BindingEvent> {
+        // Several gamepads can hold the same binding
+        let mut unique: Vec<Binding> = Vec::new();
+        for binding in held {
+            if !unique.contains(&binding) {
+                unique.push(binding);
+            }
+        }
+        let released = self
+            .held
+            .iter()
+            .filter(|binding| !unique.contains(binding))
+            .map(|&binding| BindingEvent::Release(binding));
+        let pressed = unique
+            .iter()
+            .filter(|binding| !self.held.contains(binding))
+            .map(|&binding| BindingEvent::Press(binding));
+        let events = released.chain(pressed).collect();
+        self.held = unique;
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_of_the_held_bindings_are_events() {
+        let south = Binding::Gamepad(Button::South);
+        let up = Binding::Axis(Axis::LeftStickY, AxisSide::Positive);
+        let mut input = GamepadInput::default();
+
+        assert_eq!(
+            input.update(vec![south, up, south]),
+            vec![BindingEvent::Press(south), BindingEvent::Press(up)]
+        );
+        assert!(input.is_held(south));
+        assert_eq!(input.update(vec![up]), vec![BindingEvent::Release(south)]);
+        assert!(input.update(vec![up]).is_empty());
+        assert_eq!(input.update(Vec::new()), vec![BindingEvent::Release(up)]);
+        assert!(!input.is_held(up));
+    }
+}
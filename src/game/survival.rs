@@ -0,0 +1,320 @@
+use super::controls::key_name;
+
+use crate::{
+    prelude::*,
+    render::{hud_camera, HUD_HEIGHT},
+};
+
+use std::path::PathBuf;
+
+/// A finished run in the high-score table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HighScore {
+    score: u64,
+    /// Last wave reached.
+    wave: usize,
+    kills: usize,
+    /// Unix time when the run ended.
+    time: u64,
+}
+
+/// Best runs stored in the user preferences directory, sorted by score.
+#[derive(Debug, Clone, Default)]
+struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    fn path() -> PathBuf {
+        preferences::base_path().join("highscores.json")
+    }
+
+    /// Load the table, a missing or broken file results in an empty table.
+    fn load() -> Self {
+        let path = Self::path();
+        if !path.exists() {
+            return Self::default();
+        }
+        let entries = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|source| Ok(serde_json::from_str(&source)?));
+        match entries {
+            Ok(entries) => Self { entries },
+            Err(err) => {
+                log::error!("Failed to load high scores from {:?}: {}", path, err);
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    /// Add the run keeping at most `size` best entries.
+    /// Returns the index of the run in the table if it made it.
+    fn insert(&mut self, entry: HighScore, size: usize) -> Option<usize> {
+        let index = self
+            .entries
+            .iter()
+            .position(|other| other.score < entry.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+        self.entries.truncate(size);
+        (index < self.entries.len()).then_some(index)
+    }
+}
+
+/// Escalating waves of opponents until the player dies.
+pub struct SurvivalSession {
+    geng: Geng,
+    /// Current wave number, zero before the first one.
+    wave: usize,
+    /// Time until the next wave spawns.
+    next_wave: Option<Time>,
+    score: u64,
+    kills: usize,
+    best_combo: usize,
+    /// Time survived.
+    time: Time,
+    high_scores: HighScores,
+    /// Set when the player has died.
+    summary: Option<Summary>,
+}
+
+struct Summary {
+    /// Index of the run in the high-score table.
+    rank: Option<usize>,
+    /// Problem with saving the high scores.
+    error: Option<String>,
+}
+
+impl SurvivalSession {
+    const FONT_SIZE: f32 = 12.0;
+
+    /// Start a run, replacing the opponents of the level.
+    pub fn new(geng: &Geng, model: &mut Model) -> Self {
+        model.opponents.clear();
+        model.round_time = Time::ZERO;
+        Self {
+            geng: geng.clone(),
+            wave: 0,
+            next_wave: Some(Time::ZERO),
+            score: 0,
+            kills: 0,
+            best_combo: 0,
+            time: Time::ZERO,
+            high_scores: HighScores::load(),
+            summary: None,
+        }
+    }
+
+    /// Whether the player has died, the game is paused on the summary.
+    pub fn is_over(&self) -> bool {
+        self.summary.is_some()
+    }
+
+    pub fn handle_outcome(&mut self, model: &Model, outcome: &Outcome) {
+        if self.is_over() {
+            return;
+        }
+        match *outcome {
+            Outcome::Killed {
+                fighter: Some(0), ..
+            } => self.finish(model),
+            Outcome::Killed {
+                fighter: Some(_),
+                attacker: Some(0),
+                ..
+            } => {
+                // Kills during a combo are worth more
+                let combo = model.player.combo.count.saturating_sub(1);
+                let multiplier = R32::ONE + model.config.survival.combo_bonus * r32(combo as f32);
+                self.score +=
+                    (model.config.survival.kill_score as f32 * multiplier.as_f32()) as u64;
+                self.kills += 1;
+            }
+            Outcome::ComboStep { count, .. } => {
+                self.best_combo = self.best_combo.max(count);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn update(&mut self, model: &mut Model, delta_time: Time) {
+        if self.is_over() {
+            return;
+        }
+        self.time += delta_time;
+
+        if model.opponents.is_empty() && self.next_wave.is_none() {
+            self.next_wave = Some(model.config.survival.wave_delay);
+        }
+        if let Some(time) = &mut self.next_wave {
+            *time -= delta_time;
+            if *time <= Time::ZERO {
+                self.next_wave = None;
+                self.spawn_wave(model);
+            }
+        }
+    }
+
+    fn spawn_wave(&mut self, model: &mut Model) {
+        self.wave += 1;
+        let config = &model.config.survival;
+        let count = config.first_wave + (self.wave - 1) * config.wave_growth;
+        let growth = r32((self.wave - 1) as f32);
+        let stats = FighterStats {
+            health: R32::ONE + config.health_growth * growth,
+            damage: R32::ONE + config.damage_growth * growth,
+        };
+
+        let spawn_points = if model.level.spawn_points.is_empty() {
+            model.level.arena.corners().to_vec()
+        } else {
+            model.level.spawn_points.clone()
+        };
+        for i in 0..count {
+            let position = spawn_points[(self.wave + i) % spawn_points.len()];
+            let archetypes = &model.config.ai.archetypes;
+            let archetype = archetypes[(self.wave + i) % archetypes.len()].name.clone();
+            model.spawn_opponent(position, &archetype, stats);
+        }
+        log::info!("Wave {} with {} opponents", self.wave, count);
+    }
+
+    fn finish(&mut self, model: &Model) {
+        let time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs());
+        let entry = HighScore {
+            score: self.score,
+            wave: self.wave,
+            kills: self.kills,
+            time,
+        };
+        let rank = self
+            .high_scores
+            .insert(entry, model.config.survival.high_scores);
+        let error = match self.high_scores.save() {
+            Ok(()) => None,
+            Err(err) => {
+                log::error!("Failed to save high scores: {}", err);
+                Some(format!("Failed to save high scores: {}", err))
+            }
+        };
+        self.summary = Some(Summary { rank, error });
+    }
+
+    /// Draw the wave and the score at the top, or the summary after the run.
+    pub fn draw(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let camera = hud_camera(framebuffer.size().as_f32());
+        let center = camera.center;
+
+        let Some(summary) = &self.summary else {
+            let mut lines = vec![format!("Wave {}   Score {}", self.wave, self.score)];
+            if let Some(time) = self.next_wave {
+                lines.push(format!(
+                    "Wave {} in {:.0}",
+                    self.wave + 1,
+                    time.as_f32().ceil()
+                ));
+            }
+            self.draw_lines(
+                &lines,
+                vec2(center.x, HUD_HEIGHT - 24.0),
+                &camera,
+                framebuffer,
+            );
+            return;
+        };
+
+        let panel = Aabb2::point(center).extend_symmetric(vec2(160.0, 130.0));
+        self.geng
+            .draw2d()
+            .quad(framebuffer, &camera, panel, Color::new(0.0, 0.0, 0.0, 0.8));
+
+        let mut lines = vec![
+            "You died".to_string(),
+            format!(
+                "Wave {}   Kills {}   Best combo x{}",
+                self.wave, self.kills, self.best_combo
+            ),
+            format!("Survived {:.0}s   Score {}", self.time, self.score),
+            String::new(),
+            "High scores".to_string(),
+        ];
+        for (i, entry) in self.high_scores.entries.iter().enumerate() {
+            let marker = if summary.rank == Some(i) { ">" } else { " " };
+            lines.push(format!(
+                "{}{:>2}. {:>7}  wave {:>2}  kills {:>3}",
+                marker,
+                i + 1,
+                entry.score,
+                entry.wave,
+                entry.kills
+            ));
+        }
+        lines.extend(summary.error.clone());
+        if let Some(key) = model.config.controls.survival.restart.first() {
+            lines.push(String::new());
+            lines.push(format!("Press {} to try again", key_name(key)));
+        }
+        let pos = vec2(center.x, panel.max.y - 8.0);
+        self.draw_lines(&lines, pos, &camera, framebuffer);
+    }
+
+    /// Draw the lines centered horizontally, going down from the position.
+    fn draw_lines(
+        &self,
+        lines: &[String],
+        mut pos: vec2<f32>,
+        camera: &Camera2d,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        for line in lines {
+            self.geng.default_font().draw(
+                framebuffer,
+                camera,
+                line,
+                vec2(geng::TextAlign::CENTER, geng::TextAlign::TOP),
+                mat3::translate(pos) * mat3::scale_uniform(Self::FONT_SIZE),
+                Color::WHITE,
+            );
+            pos.y -= Self::FONT_SIZE * 1.2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(score: u64) -> HighScore {
+        HighScore {
+            score,
+            wave: 1,
+            kills: 0,
+            time: 0,
+        }
+    }
+
+    #[test]
+    fn high_scores_keep_the_best_runs_sorted() {
+        let mut table = HighScores::default();
+        assert_eq!(table.insert(run(20), 3), Some(0));
+        assert_eq!(table.insert(run(30), 3), Some(0));
+        assert_eq!(table.insert(run(10), 3), Some(2));
+        // Ties go after the earlier runs
+        assert_eq!(table.insert(run(20), 3), Some(2));
+        assert_eq!(table.insert(run(5), 3), None);
+
+        let scores: Vec<u64> = table.entries.iter().map(|entry| entry.score).collect();
+        assert_eq!(scores, vec![30, 20, 20]);
+    }
+}
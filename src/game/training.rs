@@ -0,0 +1,324 @@
+use crate::{
+    prelude::*,
+    render::{hud_camera, HUD_HEIGHT},
+};
+
+/// A single contact recorded during the session.
+#[derive(Debug, Clone)]
+struct HitRecord {
+    /// Time since the start of the session.
+    time: Time,
+    intent: WeaponIntent,
+    power: R32,
+    damage: Hp,
+    arc_length: Coord,
+    /// Time from the start of the swing to the contact.
+    duration: Time,
+}
+
+/// The swing that is currently in progress.
+#[derive(Debug, Clone)]
+struct SwingRecord {
+    intent: WeaponIntent,
+    power: R32,
+    arc_length: Coord,
+    start_time: Time,
+    /// Whether the swing has made contact with anything yet.
+    landed: bool,
+}
+
+/// Contacts of the player's swings since the start of the session.
+#[derive(Debug, Clone)]
+struct TrainingStats {
+    /// Game time when the session started.
+    start_time: Time,
+    records: Vec<HitRecord>,
+    swing: Option<SwingRecord>,
+    /// Number of attacks performed.
+    swings: usize,
+    /// Number of attacks that hit something.
+    landed: usize,
+}
+
+/// Records the hits on the training dummies and shows the session stats.
+pub struct TrainingSession {
+    geng: Geng,
+    stats: TrainingStats,
+    /// Result of the last export.
+    status: Option<String>,
+}
+
+impl TrainingStats {
+    fn new(model: &Model) -> Self {
+        Self {
+            start_time: model.game_time,
+            records: Vec::new(),
+            swing: None,
+            swings: 0,
+            landed: 0,
+        }
+    }
+
+    fn handle_outcome(&mut self, model: &Model, outcome: &Outcome) {
+        // Only the player's own swings are trained
+        match *outcome {
+            Outcome::SwingStarted {
+                fighter: 0,
+                intent,
+                power,
+                arc_length,
+                ..
+            } => {
+                if let WeaponIntent::Attack = intent {
+                    self.swings += 1;
+                }
+                self.swing = Some(SwingRecord {
+                    intent,
+                    power,
+                    arc_length,
+                    start_time: model.game_time,
+                    landed: false,
+                });
+            }
+            Outcome::Hit {
+                attacker: 0,
+                damage,
+                ..
+            } => self.record(model, damage),
+            Outcome::Parried { attacker: 0, .. } => self.record(model, Hp::ZERO),
+            Outcome::SwingStarted { .. }
+            | Outcome::Hit { .. }
+            | Outcome::Parried { .. }
+            | Outcome::Clash { .. }
+            | Outcome::Feinted { .. }
+            | Outcome::Thrown { .. }
+            | Outcome::WallHit { .. }
+            | Outcome::EffectApplied { .. }
+            | Outcome::Dashed { .. }
+            | Outcome::Killed { .. }
+            | Outcome::ComboStep { .. } => {}
+        }
+    }
+
+    /// Forget the swing once it is over, so that no later contact is counted for it.
+    fn update(&mut self, model: &Model) {
+        if !model.player.weapon.action.swinging() {
+            self.swing = None;
+        }
+    }
+
+    fn record(&mut self, model: &Model, damage: Hp) {
+        let Some(swing) = &mut self.swing else {
+            return;
+        };
+        if !swing.landed {
+            swing.landed = true;
+            if let WeaponIntent::Attack = swing.intent {
+                self.landed += 1;
+            }
+        }
+        self.records.push(HitRecord {
+            time: model.game_time - self.start_time,
+            intent: swing.intent,
+            power: swing.power,
+            damage,
+            arc_length: swing.arc_length,
+            duration: model.game_time - swing.start_time,
+        });
+    }
+
+    /// Total damage divided by the session duration.
+    fn dps(&self, model: &Model) -> f32 {
+        let elapsed = (model.game_time - self.start_time).as_f32();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        let damage: f32 = self.records.iter().map(|hit| hit.damage.as_f32()).sum();
+        damage / elapsed
+    }
+
+    /// Average power of the recorded hits.
+    fn average_power(&self) -> f32 {
+        if self.records.is_empty() {
+            return 0.0;
+        }
+        let power: f32 = self.records.iter().map(|hit| hit.power.as_f32()).sum();
+        power / self.records.len() as f32
+    }
+
+    /// Fraction of the attacks that hit something.
+    fn accuracy(&self) -> f32 {
+        if self.swings == 0 {
+            return 0.0;
+        }
+        self.landed as f32 / self.swings as f32
+    }
+}
+
+impl TrainingSession {
+    /// Time span shown in the history graph.
+    const GRAPH_SPAN: f32 = 30.0;
+    const GRAPH_SIZE: vec2<f32> = vec2(120.0, 40.0);
+    const FONT_SIZE: f32 = 10.0;
+
+    pub fn new(geng: &Geng, model: &Model) -> Self {
+        Self {
+            geng: geng.clone(),
+            stats: TrainingStats::new(model),
+            status: None,
+        }
+    }
+
+    /// Clear the recorded stats and start a new session.
+    pub fn reset(&mut self, model: &Model) {
+        *self = Self::new(&self.geng, model);
+    }
+
+    pub fn handle_outcome(&mut self, model: &Model, outcome: &Outcome) {
+        self.stats.handle_outcome(model, outcome);
+    }
+
+    pub fn update(&mut self, model: &Model) {
+        self.stats.update(model);
+    }
+
+    /// Write every recorded hit to the file in the CSV format.
+    pub fn export(&mut self, path: impl AsRef<std::path::Path>) {
+        let path = path.as_ref();
+        let mut csv = String::from("time,swing,power,damage,arc_length,duration\n");
+        for hit in &self.stats.records {
+            let swing = match hit.intent {
+                WeaponIntent::Attack => "slash",
+                WeaponIntent::Defend => "parry",
+            };
+            csv += &format!(
+                "{:.3},{},{:.3},{:.3},{:.3},{:.3}\n",
+                hit.time, swing, hit.power, hit.damage, hit.arc_length, hit.duration
+            );
+        }
+        self.status = Some(match std::fs::write(path, csv) {
+            Ok(()) => {
+                log::info!("Exported training stats to {:?}", path);
+                format!("Exported to {}", path.display())
+            }
+            Err(err) => {
+                log::error!("Failed to export training stats: {}", err);
+                format!("Export failed: {}", err)
+            }
+        });
+    }
+
+    /// Draw the stats and the damage history on the left side of the screen.
+    pub fn draw(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let camera = hud_camera(framebuffer.size().as_f32());
+
+        let mut pos = vec2(8.0, HUD_HEIGHT - 40.0);
+        let lines = [
+            format!("DPS {:.1}", self.stats.dps(model)),
+            format!("Avg power {:.2}", self.stats.average_power()),
+            format!(
+                "Accuracy {:.0}% ({}/{})",
+                self.stats.accuracy() * 100.0,
+                self.stats.landed,
+                self.stats.swings
+            ),
+        ];
+        for line in lines.iter().chain(&self.status) {
+            self.geng.default_font().draw(
+                framebuffer,
+                &camera,
+                line,
+                vec2(geng::TextAlign::LEFT, geng::TextAlign::TOP),
+                mat3::translate(pos) * mat3::scale_uniform(Self::FONT_SIZE),
+                Color::WHITE,
+            );
+            pos.y -= Self::FONT_SIZE * 1.2;
+        }
+
+        // Damage history, latest hits on the right
+        let graph = Aabb2::point(pos - vec2(0.0, Self::GRAPH_SIZE.y + 4.0))
+            .extend_positive(Self::GRAPH_SIZE);
+        let draw2d = self.geng.draw2d();
+        draw2d.quad(framebuffer, &camera, graph, Color::new(0.0, 0.0, 0.0, 0.5));
+
+        let now = (model.game_time - self.stats.start_time).as_f32();
+        let max_damage = self
+            .stats
+            .records
+            .iter()
+            .map(|hit| hit.damage.as_f32())
+            .fold(1.0, f32::max);
+        for hit in &self.stats.records {
+            let age = now - hit.time.as_f32();
+            if age > Self::GRAPH_SPAN {
+                continue;
+            }
+            let x = graph.max.x - age / Self::GRAPH_SPAN * graph.width();
+            let (height, color) = match hit.intent {
+                WeaponIntent::Attack => (
+                    hit.damage.as_f32() / max_damage,
+                    model.config.palette.attack,
+                ),
+                // Parries deal no damage, show them as short markers
+                WeaponIntent::Defend => (0.1, model.config.palette.defend),
+            };
+            let bar = Aabb2::point(vec2(x, graph.min.y))
+                .extend_positive(vec2(2.0, height * graph.height()));
+            draw2d.quad(framebuffer, &camera, bar, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(attacker: usize, damage: f32) -> Outcome {
+        Outcome::Hit {
+            attacker,
+            position: vec2::ZERO,
+            normal: vec2::ZERO,
+            power: R32::ONE,
+            damage: r32(damage),
+            backstab: false,
+        }
+    }
+
+    #[test]
+    fn only_the_player_swings_are_recorded() {
+        let level = Level {
+            arena: Aabb2::ZERO.extend_symmetric(vec2(8.0, 5.0).as_r32()),
+            opponents: Vec::new(),
+            spawn_points: Vec::new(),
+        };
+        let model = Model::new(Config::default(), level);
+        let mut stats = TrainingStats::new(&model);
+        stats.handle_outcome(
+            &model,
+            &Outcome::SwingStarted {
+                fighter: 0,
+                position: vec2::ZERO,
+                stroke_center: vec2::ZERO,
+                intent: WeaponIntent::Attack,
+                power: R32::ONE,
+                speed: R32::ONE,
+                arc_length: R32::ONE,
+            },
+        );
+
+        // An opponent hitting the player during the swing
+        stats.handle_outcome(&model, &hit(1, 5.0));
+        assert!(stats.records.is_empty());
+        assert_eq!(stats.landed, 0);
+
+        stats.handle_outcome(&model, &hit(0, 3.0));
+        assert_eq!(stats.records.len(), 1);
+        assert_eq!(stats.records[0].damage, r32(3.0));
+        assert_eq!(stats.accuracy(), 1.0);
+
+        // The player is not swinging anymore
+        stats.update(&model);
+        stats.handle_outcome(&model, &hit(0, 3.0));
+        assert_eq!(stats.records.len(), 1);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::prelude::{Config, *};
+
+use geng::ui::{self, *};
+use std::ops::RangeInclusive;
+
+const FONT_SIZE: f32 = 16.0;
+
+/// Overlay for tweaking the config values of the running game.
+pub struct TuningPanel {
+    pub open: bool,
+    /// The config as loaded from the files, only the values tuned away from it are saved.
+    loaded: Config,
+    /// Message about the result of the last save.
+    status: Option<String>,
+}
+
+impl TuningPanel {
+    pub fn new(loaded: Config) -> Self {
+        Self {
+            open: false,
+            loaded,
+            status: None,
+        }
+    }
+
+    /// Update the config compared against after it is reloaded.
+    pub fn set_loaded(&mut self, loaded: Config) {
+        self.loaded = loaded;
+    }
+
+    /// Write the tuned values into the [UserSettings].
+    fn save(&self, config: &Config) -> anyhow::Result<()> {
+        let mut settings = UserSettings::load();
+        settings.set_changed(&self.loaded, config)?;
+        settings.save()
+    }
+
+    pub fn ui<'a>(
+        &'a mut self,
+        cx: &'a ui::Controller,
+        model: &'a mut Model,
+    ) -> Box<dyn ui::Widget + 'a> {
+        if !self.open {
+            return Box::new(ui::Void);
+        }
+
+        let font = cx.theme().font.clone();
+        let text = |text: String| ui::Text::new(text, font.clone(), FONT_SIZE, Color::WHITE);
+
+        let errors = model.config.validate();
+        let save = ui::Button::new(cx, "Save");
+        // Invalid values would stop the next launch, so they are never written
+        let save: Box<dyn ui::Widget + 'a> = if errors.is_empty() {
+            if save.was_clicked() {
+                self.status = Some(match self.save(&model.config) {
+                    Ok(()) => format!("Saved to {:?}", UserSettings::path()),
+                    Err(err) => format!("Failed to save: {}", err),
+                });
+            }
+            Box::new(save)
+        } else {
+            Box::new(ui::Text::new(
+                "Save (fix the errors first)",
+                font.clone(),
+                FONT_SIZE,
+                Color::GRAY,
+            ))
+        };
+
+        let weapon = &model.player.weapon;
+        let action = match &weapon.action {
+            WeaponAction::Idle { .. } => "Idle".to_string(),
+            WeaponAction::Charging { intent, .. } => format!("Charging {:?}", intent),
+            WeaponAction::Swing(swing) => {
+                format!("Swing {:?}, power {:.2}", swing.intent, swing.power)
+            }
+            WeaponAction::Bind { partner, .. } => format!("Bound with fighter {}", partner),
+        };
+        let state = ui::column![
+            text(format!("Action: {}", action)),
+            text(format!("Weapon speed: {:.2}", weapon.velocity.len())),
+        ];
+
+        let audio = &mut model.config.audio;
+        let cursor = &mut model.config.cursor;
+        let player = &mut model.config.player;
+        let weapon = &mut model.config.weapon;
+        let charge = &mut model.config.charge;
+        // The trail fades out between the two times, so they must not cross
+        let fade_max = (cursor.trail_time.as_f32() - 0.01).max(0.0);
+        let trail_min = (cursor.fade_time.as_f32() + 0.01).clamp(0.05, 1.0);
+        let sliders = ui::column![
+            text("Cursor".to_string()),
+            slider(cx, "sensitivity", &mut cursor.sensitivity, 0.1..=5.0),
+            slider(cx, "trail_time", &mut cursor.trail_time, trail_min..=1.0),
+            slider(cx, "fade_time", &mut cursor.fade_time, 0.0..=fade_max),
+            text("Weapon".to_string()),
+            slider(cx, "acceleration", &mut weapon.acceleration, 1.0..=200.0),
+            slider(cx, "swing_boost", &mut weapon.swing_boost, 0.1..=5.0),
+            slider(cx, "speed_max", &mut weapon.speed_max, 1.0..=30.0),
+            slider(cx, "mass", &mut weapon.mass, 0.1..=5.0),
+            slider(cx, "power_min", &mut weapon.power_min, 0.0..=10.0),
+            slider(cx, "power_max", &mut weapon.power_max, 0.0..=10.0),
+            text("Charge".to_string()),
+            slider(cx, "full_time", &mut charge.full_time, 0.05..=2.0),
+            slider(cx, "hold_time", &mut charge.hold_time, 0.0..=3.0),
+            slider(
+                cx,
+                "overcharge_power",
+                &mut charge.overcharge_power,
+                0.0..=1.0
+            ),
+            text("Audio".to_string()),
+            slider(cx, "master_volume", &mut audio.master_volume, 0.0..=1.0),
+            slider(cx, "sfx_volume", &mut audio.sfx_volume, 0.0..=1.0),
+            slider(cx, "music_volume", &mut audio.music_volume, 0.0..=1.0),
+            text("Player".to_string()),
+            slider(cx, "walk_speed", &mut player.walk_speed, 0.1..=10.0),
+            slider(cx, "acceleration", &mut player.acceleration, 1.0..=50.0),
+        ];
+
+        let mut panel = ui::column![state, sliders, save];
+        for error in errors {
+            panel.push(Box::new(ui::Text::new(
+                error.to_string(),
+                cx.theme().font.clone(),
+                FONT_SIZE,
+                Color::RED,
+            )));
+        }
+        if let Some(status) = &self.status {
+            panel.push(Box::new(text(status.clone())));
+        }
+
+        Box::new(
+            ui::stack![
+                ui::ColorBox::new(Color::new(0.0, 0.0, 0.0, 0.7)),
+                panel.uniform_padding(8.0)
+            ]
+            .align(vec2(1.0, 1.0)),
+        )
+    }
+}
+
+/// A labeled slider editing the value in place.
+fn slider<'a>(
+    cx: &'a ui::Controller,
+    name: &str,
+    value: &'a mut R32,
+    range: RangeInclusive<f32>,
+) -> impl ui::Widget + 'a {
+    let label = ui::Text::new(
+        format!("{}: {:.2}", name, value),
+        cx.theme().font.clone(),
+        FONT_SIZE,
+        Color::WHITE,
+    );
+    let range = *range.start() as f64..=*range.end() as f64;
+    let slider = ui::Slider::new(
+        cx,
+        value.as_f32() as f64,
+        range,
+        Box::new(move |new_value| *value = r32(new_value as f32)),
+    );
+    ui::row![
+        label.fixed_size(vec2(150.0, FONT_SIZE as f64)),
+        slider.fixed_size(vec2(200.0, FONT_SIZE as f64)),
+    ]
+}
@@ -1,14 +1,35 @@
 mod assets;
+mod audio;
 mod game;
 mod model;
 mod prelude;
 mod render;
+mod simulation;
 mod util;
 
 use prelude::*;
 
 #[derive(clap::Parser)]
+#[command(about = "Sword fighting with the weapon steered by the cursor")]
 struct Opts {
+    /// Validate the config and exit without opening a window.
+    #[clap(long)]
+    check_config: bool,
+    /// Print every config value with its source and exit.
+    #[clap(long)]
+    config_sources: bool,
+    /// Override a config value, e.g. `--set weapon.speed_max=9`.
+    /// The value is parsed as JSON, or taken as a string if that fails.
+    #[clap(long = "set", value_name = "PATH=VALUE")]
+    overrides: Vec<String>,
+    /// Start in the training mode with the session stats shown.
+    #[clap(long)]
+    training: bool,
+    /// Start in the survival mode, fighting waves of opponents.
+    #[clap(long)]
+    survival: bool,
+    #[clap(flatten)]
+    simulation: simulation::SimulationOpts,
     #[clap(flatten)]
     geng: geng::CliArgs,
 }
@@ -19,6 +40,27 @@ fn main() {
 
     let opts: Opts = clap::Parser::parse();
 
+    let layers = ConfigLayers {
+        assets: run_dir().join("assets").join("config.ron"),
+        user: Some(UserSettings::path()),
+        overrides: opts.overrides.clone(),
+    };
+    if opts.config_sources || opts.check_config || opts.simulation.simulate.is_some() {
+        // Nothing to show a window for
+        let (layered, level) = exit_on_error(futures::executor::block_on(load_config(&layers)));
+        if opts.config_sources {
+            print!("{}", layered.report());
+        } else if opts.check_config {
+            println!("Config {:?} is valid", layers.assets);
+        } else {
+            exit_on_error(
+                simulation::run(&layered.config, &level, &opts.simulation)
+                    .map_err(|err| err.to_string()),
+            );
+        }
+        return;
+    }
+
     let mut options = geng::ContextOptions::default();
     options.window.title = "Geng Game".to_string();
     options.window.antialias = false;
@@ -34,12 +76,39 @@ fn main() {
             .unwrap();
         let assets = Rc::new(assets);
 
-        let config =
-            <Config as geng::asset::Load>::load(manager, &assets_path.join("config.ron"), &())
-                .await
-                .unwrap();
+        let (layered, level) = exit_on_error(load_config(&layers).await);
+        let config_watcher = ConfigWatcher::new(layers);
 
-        let state = game::State::new(&geng, &assets, config);
+        let mut state = game::State::new(&geng, &assets, layered, level, config_watcher);
+        if opts.training {
+            state.start_training();
+        }
+        if opts.survival {
+            state.start_survival();
+        }
         geng.run_state(state).await;
     });
 }
+
+/// Load the layered config and the level from the assets.
+async fn load_config(layers: &ConfigLayers) -> Result<(LayeredConfig, Level), String> {
+    let layered = layers.load().await?;
+    let level_path = run_dir().join("assets").join("level.ron");
+    let level: Level = load_ron(&level_path).await?;
+    let errors = level.validate(&layered.config);
+    if !errors.is_empty() {
+        let mut message = format!("Invalid level {:?}:", level_path);
+        for error in errors {
+            message += &format!("\n  {}", error);
+        }
+        return Err(message);
+    }
+    Ok((layered, level))
+}
+
+fn exit_on_error<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    })
+}
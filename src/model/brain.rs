@@ -0,0 +1,173 @@
+use super::*;
+
+/// Computer control of a fighter, picks the action with the highest utility.
+#[derive(Debug, Clone)]
+pub struct Brain {
+    /// Name of the archetype from [AiConfig::archetypes].
+    pub archetype: String,
+    pub action: AiAction,
+    /// Time when the target started threatening, reset when it stops.
+    pub noticed: Option<Time>,
+    /// Time of the last attack.
+    pub attack_time: Option<Time>,
+    /// Time of the last parry.
+    pub guard_time: Option<Time>,
+    /// Direction of circling around the target, `1` or `-1`.
+    pub strafe_side: Coord,
+    /// Reaction time to the current threat, varied around the archetype's one.
+    pub reaction_time: Time,
+    /// Charge time of the current attack, varied around the archetype's one.
+    pub charge_time: Time,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiAction {
+    /// Close the distance to the target.
+    Approach,
+    /// Circle around the target at the preferred range.
+    Strafe,
+    /// Charge and swing at the target.
+    Attack,
+    /// Swing defensively across the incoming attack.
+    Parry,
+    /// Back away from the target.
+    Retreat,
+}
+
+/// What a fighter knows about its target.
+#[derive(Debug, Clone)]
+pub struct Perception {
+    pub distance: Coord,
+    /// Current action of the target's weapon.
+    pub target_action: WeaponAction,
+    /// Time since the target started charging or swinging an attack.
+    pub threat_time: Option<Time>,
+    /// Delay before reacting to the threat.
+    pub reaction_time: Time,
+    /// Health of the fighter in range `0..=1`.
+    pub health: R32,
+    /// Whether the weapon of the fighter reaches the target.
+    pub in_reach: bool,
+    pub can_attack: bool,
+    pub can_parry: bool,
+}
+
+impl AiAction {
+    pub const ALL: [Self; 5] = [
+        Self::Approach,
+        Self::Strafe,
+        Self::Attack,
+        Self::Parry,
+        Self::Retreat,
+    ];
+}
+
+impl Perception {
+    /// Whether the target is charging an attack.
+    pub fn target_charging(&self) -> bool {
+        matches!(
+            self.target_action,
+            WeaponAction::Charging {
+                intent: WeaponIntent::Attack,
+                ..
+            }
+        )
+    }
+
+    /// Whether the target is swinging an attack.
+    pub fn target_swinging(&self) -> bool {
+        matches!(
+            self.target_action,
+            WeaponAction::Swing(WeaponSwing {
+                intent: WeaponIntent::Attack,
+                ..
+            })
+        )
+    }
+}
+
+impl Brain {
+    pub fn new(archetype: String) -> Self {
+        Self {
+            archetype,
+            action: AiAction::Approach,
+            noticed: None,
+            attack_time: None,
+            guard_time: None,
+            strafe_side: Coord::ONE,
+            reaction_time: Time::ZERO,
+            charge_time: Time::ZERO,
+        }
+    }
+
+    /// Pick the action with the highest weighted utility.
+    pub fn decide(
+        &self,
+        perception: &Perception,
+        archetype: &ArchetypeConfig,
+        config: &AiConfig,
+    ) -> AiAction {
+        AiAction::ALL
+            .into_iter()
+            .max_by_key(|&action| {
+                let mut score = Self::utility(action, perception, archetype, config);
+                if action == self.action && score > R32::ZERO {
+                    score += config.commitment;
+                }
+                score
+            })
+            .unwrap_or(AiAction::Approach)
+    }
+
+    /// Weighted desirability of the action in range `0..=weight`.
+    fn utility(
+        action: AiAction,
+        perception: &Perception,
+        archetype: &ArchetypeConfig,
+        config: &AiConfig,
+    ) -> R32 {
+        let range = archetype.preferred_range.max(r32(0.1));
+        // How far off the preferred range the target is, relative to the range
+        let offset = (perception.distance - range) / range;
+        let reacted = perception
+            .threat_time
+            .is_some_and(|time| time >= perception.reaction_time);
+        let weights = &archetype.weights;
+
+        let (score, weight) = match action {
+            AiAction::Approach => (offset.clamp(R32::ZERO, R32::ONE), weights.approach),
+            AiAction::Strafe => (
+                (R32::ONE - offset.abs()).clamp(R32::ZERO, R32::ONE) * r32(0.5),
+                weights.strafe,
+            ),
+            AiAction::Attack => {
+                let ready = perception.in_reach && perception.can_attack;
+                let score = if !ready {
+                    R32::ZERO
+                } else if perception.target_swinging() {
+                    // Trading blows is worse than waiting the swing out
+                    r32(0.3)
+                } else {
+                    r32(0.9)
+                };
+                (score, weights.attack)
+            }
+            AiAction::Parry => {
+                let ready =
+                    reacted && perception.can_parry && perception.distance <= config.guard_range;
+                (if ready { R32::ONE } else { R32::ZERO }, weights.parry)
+            }
+            AiAction::Retreat => {
+                let crowded = (-offset).clamp(R32::ZERO, R32::ONE);
+                // Wounded fighters are more careful around a threat
+                let danger = if reacted {
+                    (R32::ONE - perception.health) * r32(0.8)
+                } else {
+                    R32::ZERO
+                };
+                (crowded.max(danger), weights.retreat)
+            }
+        };
+        score * weight
+    }
+}
@@ -4,8 +4,10 @@ pub use self::shape::*;
 
 use super::*;
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 pub struct Collision {
+    /// World position of the contact on the surface of the first body.
     pub point: vec2<Coord>,
     /// Normal vector pointing away from the body.
     pub normal: vec2<Coord>,
@@ -123,7 +125,7 @@ impl Collider {
             let normal = contact.normal1.into_inner();
             let point = contact.point1;
             Collision {
-                point: vec2(point.x, point.y).map(Coord::new),
+                point: self.position + vec2(point.x, point.y).map(Coord::new),
                 normal: vec2(normal.x, normal.y).map(Coord::new),
                 penetration: Coord::new(-contact.dist),
             }
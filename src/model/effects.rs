@@ -0,0 +1,120 @@
+use super::*;
+
+/// Timed status effects on a fighter or a mannequin.
+#[derive(Debug, Clone, Default)]
+pub struct StatusEffects {
+    pub active: Vec<ActiveEffect>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveEffect {
+    pub effect: StatusEffect,
+    /// Time left until the effect wears off.
+    pub remaining: Time,
+    /// Index into [Model::fighters] of the fighter that applied the effect.
+    pub source: Option<usize>,
+}
+
+impl StatusEffects {
+    /// Start the effect, an effect of the same kind is replaced if it would wear off sooner.
+    pub fn apply(&mut self, effect: StatusEffect, duration: Time, source: Option<usize>) {
+        let same = self.active.iter_mut().find(|active| {
+            std::mem::discriminant(&active.effect) == std::mem::discriminant(&effect)
+        });
+        match same {
+            Some(active) => {
+                if active.remaining <= duration {
+                    *active = ActiveEffect {
+                        effect,
+                        remaining: duration,
+                        source,
+                    };
+                }
+            }
+            None => self.active.push(ActiveEffect {
+                effect,
+                remaining: duration,
+                source,
+            }),
+        }
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.active
+            .iter()
+            .any(|active| matches!(active.effect, StatusEffect::Stun))
+    }
+
+    /// Multiplier of the movement and the weapon acceleration.
+    pub fn slow_factor(&self) -> R32 {
+        self.active
+            .iter()
+            .filter_map(|active| match active.effect {
+                StatusEffect::Slow { factor } => Some(factor),
+                _ => None,
+            })
+            .fold(R32::ONE, R32::min)
+    }
+
+    /// The fighter who caused the bleeding, if any.
+    pub fn bleed_source(&self) -> Option<usize> {
+        self.active.iter().find_map(|active| match active.effect {
+            StatusEffect::Bleed { .. } => active.source,
+            _ => None,
+        })
+    }
+
+    /// Count down the effects and remove the expired ones.
+    /// Returns the bleeding damage taken over the time.
+    pub fn update(&mut self, delta_time: Time) -> Hp {
+        let mut damage = Hp::ZERO;
+        for active in &mut self.active {
+            let time = active.remaining.min(delta_time);
+            active.remaining -= delta_time;
+            if let StatusEffect::Bleed { damage: dps } = active.effect {
+                damage += dps * time;
+            }
+        }
+        self.active.retain(|active| active.remaining > Time::ZERO);
+        damage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_expire_after_their_duration() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Stun, r32(1.0), None);
+        effects.update(r32(0.6));
+        assert!(effects.is_stunned());
+        effects.update(r32(0.6));
+        assert!(!effects.is_stunned());
+        assert!(effects.active.is_empty());
+    }
+
+    #[test]
+    fn longer_effect_of_the_same_kind_is_kept() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Slow { factor: r32(0.5) }, r32(2.0), None);
+        effects.apply(StatusEffect::Slow { factor: r32(0.8) }, r32(1.0), None);
+        assert_eq!(effects.active.len(), 1);
+        assert_eq!(effects.slow_factor(), r32(0.5));
+
+        effects.apply(StatusEffect::Slow { factor: r32(0.8) }, r32(3.0), None);
+        assert_eq!(effects.active.len(), 1);
+        assert_eq!(effects.slow_factor(), r32(0.8));
+    }
+
+    #[test]
+    fn bleeding_deals_damage_until_it_expires() {
+        let mut effects = StatusEffects::default();
+        effects.apply(StatusEffect::Bleed { damage: r32(4.0) }, r32(1.0), None);
+        assert_eq!(effects.update(r32(0.5)), r32(2.0));
+        // Only the remaining half a second bleeds
+        assert_eq!(effects.update(r32(1.0)), r32(2.0));
+        assert_eq!(effects.update(r32(1.0)), Hp::ZERO);
+    }
+}
@@ -3,7 +3,13 @@ use super::*;
 /// Event sent to the model.
 #[derive(Debug, Clone)]
 pub enum Event {
-    CursorMove { delta: vec2<Coord> },
+    CursorMove {
+        delta: vec2<Coord>,
+    },
     Charge(WeaponIntent),
     Release,
+    Dash,
+    /// Cancel the current charge.
+    Feint,
+    Throw,
 }
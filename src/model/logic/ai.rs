@@ -0,0 +1,264 @@
+use super::*;
+
+impl Model {
+    /// Add an opponent of the archetype with the base stats scaled.
+    pub fn spawn_opponent(&mut self, position: vec2<Coord>, archetype: &str, stats: FighterStats) {
+        let fighter = self.create_bot(position, archetype, stats);
+        self.opponents.push(fighter);
+    }
+
+    /// A computer-controlled fighter of the archetype with the base stats scaled.
+    pub fn create_bot(
+        &self,
+        position: vec2<Coord>,
+        archetype: &str,
+        stats: FighterStats,
+    ) -> Fighter {
+        let mut fighter = Fighter::new(position, &self.config);
+        let mut name = archetype.to_string();
+        let mut stats = stats;
+        let ai = &self.config.ai;
+        match ai.archetype(archetype).or(ai.archetypes.first()) {
+            Some(config) => {
+                if config.name != archetype {
+                    log::warn!("Unknown archetype {:?}, using {:?}", archetype, config.name);
+                }
+                name = config.name.clone();
+                stats.health *= config.health;
+                stats.damage *= config.damage;
+            }
+            None => log::warn!("No archetypes configured for {:?}", archetype),
+        }
+        fighter.stats = stats;
+        fighter.health = Bounded::new_max(self.config.player.health * stats.health);
+        fighter.brain = Some(Brain::new(name));
+        fighter
+    }
+
+    /// Fighters with a brain perceive their target and act on the most useful action.
+    /// Attacks and parries commit to a swing, so a feint can bait them out.
+    pub(super) fn control_ai(&mut self, _delta_time: Time) {
+        let count = 1 + self.opponents.len();
+        for i in 0..count {
+            if self.fighter(i).brain.is_none() {
+                continue;
+            }
+            let target = match i {
+                _ if !self.fighter(i).is_alive() => None,
+                // Opponents go after the player, the player after the nearest opponent
+                0 => self.nearest_opponent(),
+                _ => self.player.is_alive().then_some(0),
+            };
+            let Some(target) = target else {
+                let fighter = self.fighter_mut(i);
+                fighter.target_move_dir = vec2::ZERO;
+                fighter.release_weapon();
+                continue;
+            };
+            self.think(i, target);
+        }
+    }
+
+    fn nearest_opponent(&self) -> Option<usize> {
+        let position = self.player.position;
+        self.opponents
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, opponent)| (opponent.position - position).len())
+            .map(|(i, _)| i + 1)
+    }
+
+    /// Perceive the target, decide on the action and carry it out.
+    fn think(&mut self, index: usize, target: usize) {
+        let game_time = self.game_time;
+        let Some(brain) = &self.fighter(index).brain else {
+            return;
+        };
+        let Some(archetype) = self.config.ai.archetype(&brain.archetype) else {
+            return;
+        };
+        let weapon = self
+            .config
+            .armed(self.fighter(index).weapon.name.as_deref());
+        let attack_power = self.charge(weapon, brain.charge_time).power;
+        // Vary the timings so that no two fights play out the same
+        let jitter = self.config.ai.jitter.as_f32();
+        let mut vary = |time: Time| time * r32(1.0 + self.rng.gen_range(-jitter..=jitter));
+        let reaction_time = vary(archetype.reaction_time);
+        let charge_time = vary(archetype.charge_time);
+        let target_position = self.fighter(target).position;
+        let target_action = self.fighter(target).weapon.action.clone();
+        let config = &self.config.ai;
+        let disengage = self.config.clash.disengage;
+
+        // Borrowed apart from the config
+        let fighter = match index {
+            0 => &mut self.player,
+            _ => &mut self.opponents[index - 1],
+        };
+        let delta = target_position - fighter.position;
+        let direction = delta.normalize_or_zero();
+        // Hold the blade off the line so the blades of two facing fighters do not cross
+        let reach = fighter.weapon.reach;
+        fighter.cursor.pos =
+            direction * reach * r32(0.4) + direction.rotate_90() * reach * r32(0.25);
+
+        let Some(brain) = &mut fighter.brain else {
+            return;
+        };
+        if fighter.effects.is_stunned() {
+            brain.noticed = None;
+            fighter.target_move_dir = vec2::ZERO;
+            if !fighter.weapon.action.swinging() && !fighter.weapon.action.is_bound() {
+                fighter.release_weapon();
+            }
+            return;
+        }
+
+        let mut perception = Perception {
+            distance: delta.len(),
+            target_action,
+            threat_time: None,
+            reaction_time: brain.reaction_time,
+            health: fighter.health.get_ratio(),
+            in_reach: delta.len() <= fighter.weapon.reach + r32(0.5),
+            can_attack: brain
+                .attack_time
+                .is_none_or(|time| game_time - time >= archetype.attack_cooldown),
+            can_parry: brain
+                .guard_time
+                .is_none_or(|time| game_time - time >= archetype.guard_cooldown),
+        };
+        if perception.target_charging() || perception.target_swinging() {
+            let noticed = *brain.noticed.get_or_insert_with(|| {
+                // Every new threat takes a different time to react to
+                brain.reaction_time = reaction_time;
+                game_time
+            });
+            perception.reaction_time = brain.reaction_time;
+            perception.threat_time = Some(game_time - noticed);
+        } else {
+            brain.noticed = None;
+        }
+
+        let action = &fighter.weapon.action;
+        if action.is_bound() {
+            // Wrench the blade sideways out of the bind, then take a moment before attacking again
+            let side = direction.rotate_90() * brain.strafe_side;
+            fighter.cursor.pos = fighter.weapon.position + side * disengage * r32(1.5);
+            brain.attack_time = Some(game_time);
+        }
+        let busy = action.swinging() || action.is_bound();
+        let charging = brain.action == AiAction::Attack
+            && matches!(
+                action,
+                WeaponAction::Charging {
+                    intent: WeaponIntent::Attack,
+                    ..
+                }
+            );
+        if !busy && !charging {
+            let decision = brain.decide(&perception, archetype, config);
+            if decision == AiAction::Strafe && brain.action != AiAction::Strafe {
+                // Alternate the side to be less predictable
+                brain.strafe_side = -brain.strafe_side;
+            }
+            brain.action = decision;
+        }
+
+        let range = archetype.preferred_range;
+        let side = brain.strafe_side;
+        let action = brain.action;
+        fighter.target_move_dir = match action {
+            AiAction::Approach => direction,
+            AiAction::Retreat => -direction,
+            AiAction::Strafe => {
+                let correction = (perception.distance - range).clamp(-Coord::ONE, Coord::ONE);
+                (direction.rotate_90() * side + direction * correction * r32(0.5))
+                    .normalize_or_zero()
+            }
+            AiAction::Attack if !perception.in_reach => direction,
+            AiAction::Attack | AiAction::Parry => vec2::ZERO,
+        };
+        if busy {
+            return;
+        }
+
+        match action {
+            AiAction::Attack => match fighter.weapon.action {
+                WeaponAction::Charging { start_time, .. } if charging => {
+                    if game_time - start_time >= brain.charge_time {
+                        brain.attack_time = Some(game_time);
+                        let swing = fighter.swing(
+                            index,
+                            WeaponIntent::Attack,
+                            direction,
+                            attack_power,
+                            game_time,
+                        );
+                        self.outcomes.push(swing);
+                    }
+                }
+                _ => {
+                    // Wind up on the side the swing starts from
+                    let windup =
+                        direction * reach * r32(0.5) + direction.rotate_90() * reach * r32(0.6);
+                    brain.charge_time = charge_time;
+                    fighter.weapon.action = WeaponAction::Charging {
+                        target: windup,
+                        intent: WeaponIntent::Attack,
+                        start_time: game_time,
+                    };
+                }
+            },
+            AiAction::Parry => {
+                brain.noticed = None;
+                brain.guard_time = Some(game_time);
+                brain.action = AiAction::Strafe;
+                let swing = fighter.swing(
+                    index,
+                    WeaponIntent::Defend,
+                    direction,
+                    archetype.guard_power,
+                    game_time,
+                );
+                self.outcomes.push(swing);
+            }
+            AiAction::Approach | AiAction::Strafe | AiAction::Retreat => {
+                fighter.release_weapon();
+            }
+        }
+    }
+}
+
+impl Fighter {
+    /// Commit a swing across the direction, the fighter is at `index` in [Model::fighters].
+    fn swing(
+        &mut self,
+        index: usize,
+        intent: WeaponIntent,
+        direction: vec2<Coord>,
+        power: R32,
+        game_time: Time,
+    ) -> Outcome {
+        let reach = self.weapon.reach;
+        let front = direction * reach * r32(0.7);
+        let side = direction.rotate_90() * reach * r32(0.6);
+        let arc = Parabola::new([front + side, direction * reach, front - side]);
+        self.weapon.action = WeaponAction::Swing(WeaponSwing {
+            intent,
+            start_time: game_time,
+            power,
+            arc,
+        });
+        Outcome::SwingStarted {
+            fighter: index,
+            position: self.position + self.weapon.position,
+            stroke_center: self.position + front,
+            intent,
+            power,
+            speed: self.weapon.velocity.len(),
+            arc_length: r32(arc.map(R32::as_f32).chain(16).length()),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+impl CameraController {
+    pub fn add_trauma(&mut self, trauma: R32) {
+        self.trauma = (self.trauma + trauma).min(R32::ONE);
+    }
+}
+
+impl Model {
+    pub(super) fn update_camera(&mut self, delta_time: Time) {
+        let config = &self.config.camera;
+        let controller = &mut self.camera_controller;
+
+        // Follow the player once they leave the dead zone
+        let mut target = controller.center;
+        let delta = self.player.position - target;
+        if delta.len() > config.dead_zone {
+            target = self.player.position - delta.normalize_or_zero() * config.dead_zone;
+        }
+
+        // Zoom out to fit all combatants
+        let combatants = self
+            .mannequins
+            .iter()
+            .map(|mannequin| mannequin.collider.position)
+            .chain(self.opponents.iter().map(|opponent| opponent.position));
+        let mut fov = config.fov_min;
+        for pos in combatants {
+            let delta = (pos - target).map(Coord::abs);
+            let required =
+                (delta.y).max(delta.x / controller.aspect) * r32(2.0) + config.fit_margin;
+            fov = fov.max(required);
+        }
+        let fov = fov.min(config.fov_max);
+
+        // Smooth towards the target
+        let t = R32::ONE - (-config.smoothing * delta_time).exp();
+        controller.center += (target - controller.center) * t;
+        controller.fov += (fov - controller.fov) * t;
+
+        // Keep the view inside the arena
+        let arena = self.level.arena;
+        let half_size = vec2(controller.fov * controller.aspect, controller.fov) / r32(2.0);
+        let clamp = |center: Coord, min: Coord, max: Coord, half: Coord| {
+            if max - min <= half * r32(2.0) {
+                (min + max) / r32(2.0)
+            } else {
+                center.clamp(min + half, max - half)
+            }
+        };
+        controller.center = vec2(
+            clamp(controller.center.x, arena.min.x, arena.max.x, half_size.x),
+            clamp(controller.center.y, arena.min.y, arena.max.y, half_size.y),
+        );
+
+        // Screen shake
+        controller.trauma = (controller.trauma - config.trauma_decay * delta_time).max(R32::ZERO);
+        let shake = controller.trauma * controller.trauma;
+        let rng = &mut self.rng;
+        let offset = vec2(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)).as_r32()
+            * config.shake_offset
+            * shake;
+        let angle = r32(rng.gen_range(-1.0..=1.0)) * config.shake_angle * shake;
+
+        self.camera.center = (controller.center + offset).as_f32();
+        self.camera.rotation = Angle::from_degrees(angle.as_f32());
+        self.camera.fov = controller.fov.as_f32();
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+
+impl Model {
+    /// Detect blades passing through each other, bounce or bind them.
+    pub(super) fn weapon_clashes(&mut self, _delta_time: Time) {
+        self.release_binds();
+
+        let count = 1 + self.opponents.len();
+        for a in 0..count {
+            for b in a + 1..count {
+                self.check_clash(a, b);
+            }
+        }
+    }
+
+    /// Break the binds where either side has pulled away or is no longer bound.
+    fn release_binds(&mut self) {
+        let count = 1 + self.opponents.len();
+        let disengage = self.config.clash.disengage;
+        for i in 0..count {
+            let fighter = self.fighter(i);
+            let WeaponAction::Bind { partner, .. } = fighter.weapon.action else {
+                continue;
+            };
+            let mutual = partner < count
+                && matches!(
+                    self.fighter(partner).weapon.action,
+                    WeaponAction::Bind { partner: other, .. } if other == i
+                );
+            let pulled = |fighter: &Fighter| {
+                (fighter.cursor.pos - fighter.weapon.position).len() > disengage
+            };
+            if mutual && !pulled(fighter) && !pulled(self.fighter(partner)) {
+                continue;
+            }
+
+            // The cooldown keeps the blades from binding again right away
+            let game_time = self.game_time;
+            for j in [i, partner] {
+                if j == i || mutual {
+                    let fighter = self.fighter_mut(j);
+                    fighter.release_weapon();
+                    fighter.weapon.clash_time = Some(game_time);
+                }
+            }
+        }
+    }
+
+    fn check_clash(&mut self, a: usize, b: usize) {
+        let config = &self.config.clash;
+        let (first, second) = (self.fighter(a), self.fighter(b));
+        if matches!(first.weapon.action, WeaponAction::Bind { partner, .. } if partner == b) {
+            return;
+        }
+        let recent = |fighter: &Fighter| {
+            fighter
+                .weapon
+                .clash_time
+                .is_some_and(|time| self.game_time - time < config.cooldown)
+        };
+        if recent(first) || recent(second) {
+            return;
+        }
+        let Some(collision) = first.blade_collider().collide(&second.blade_collider()) else {
+            return;
+        };
+
+        // Only blades moving into each other clash
+        let normal = collision.normal;
+        let relative =
+            (first.velocity + first.weapon.velocity) - (second.velocity + second.weapon.velocity);
+        let speed = vec2::dot(relative, normal);
+        if speed <= Coord::ZERO {
+            return;
+        }
+
+        let bound = speed < config.bind_speed;
+        let game_time = self.game_time;
+        if bound {
+            for (i, partner) in [(a, b), (b, a)] {
+                let weapon = &mut self.fighter_mut(i).weapon;
+                weapon.velocity = vec2::ZERO;
+                weapon.action = WeaponAction::Bind {
+                    partner,
+                    start_time: game_time,
+                };
+                weapon.clash_time = Some(game_time);
+            }
+        } else {
+            // Exchange momentum along the contact normal, heavier weapons are deflected less
+            let (first_mass, second_mass) = (first.weapon.mass, second.weapon.mass);
+            let impulse = (R32::ONE + config.restitution) * speed
+                / (first_mass.recip() + second_mass.recip());
+            let push = normal * collision.penetration / r32(2.0);
+            for (i, delta, push) in [
+                (a, -normal * impulse / first_mass, -push),
+                (b, normal * impulse / second_mass, push),
+            ] {
+                let fighter = self.fighter_mut(i);
+                fighter.weapon.velocity += delta;
+                fighter.weapon.position += push;
+                fighter.weapon.clash_time = Some(game_time);
+                // The swing is deflected
+                if fighter.weapon.action.swinging() {
+                    fighter.release_weapon();
+                }
+            }
+        }
+
+        self.outcomes.push(Outcome::Clash {
+            position: collision.point,
+            normal,
+            speed,
+            bound,
+        });
+    }
+}
+
+impl Fighter {
+    /// Return the weapon to following the cursor.
+    pub(super) fn release_weapon(&mut self) {
+        self.weapon.action = WeaponAction::Idle {
+            target: self.cursor.pos,
+        };
+    }
+}
@@ -0,0 +1,341 @@
+use super::*;
+
+/// Something a weapon can hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Target {
+    Mannequin(usize),
+    /// Index into [Model::fighters], the player comes first.
+    Fighter(usize),
+}
+
+/// A weapon making contact with a target, either in a swing or thrown.
+#[derive(Debug, Clone)]
+pub(super) struct Strike {
+    /// Index into [Model::fighters].
+    pub attacker: usize,
+    pub intent: WeaponIntent,
+    pub power: R32,
+    pub mass: R32,
+    /// World velocity of the striking object.
+    pub velocity: vec2<Coord>,
+    /// World position the strike is coming from.
+    pub origin: vec2<Coord>,
+    /// Applied to the target unless blocked.
+    pub effects: Vec<EffectConfig>,
+}
+
+impl Model {
+    /// The player followed by the opponents.
+    pub fn fighters(&self) -> impl Iterator<Item = &Fighter> {
+        std::iter::once(&self.player).chain(&self.opponents)
+    }
+
+    pub(super) fn fighter(&self, index: usize) -> &Fighter {
+        match index {
+            0 => &self.player,
+            _ => &self.opponents[index - 1],
+        }
+    }
+
+    pub(super) fn fighter_mut(&mut self, index: usize) -> &mut Fighter {
+        match index {
+            0 => &mut self.player,
+            _ => &mut self.opponents[index - 1],
+        }
+    }
+
+    pub(super) fn weapon_hits(&mut self, _delta_time: Time) {
+        // Gather the swings first since the targets are changed when resolving the hits
+        let swings: Vec<(usize, Collider, WeaponSwing)> = self
+            .fighters()
+            .enumerate()
+            .filter(|(_, fighter)| fighter.is_alive())
+            .filter_map(|(i, fighter)| match &fighter.weapon.action {
+                WeaponAction::Swing(swing) => Some((i, fighter.weapon_collider(), swing.clone())),
+                _ => None,
+            })
+            .collect();
+
+        let hit_cooldown = r32(0.5);
+        for (attacker, weapon_collider, swing) in swings {
+            let mut contacts = Vec::new();
+            for (i, mannequin) in self.mannequins.iter().enumerate() {
+                if !mannequin.is_alive() || self.game_time - mannequin.hit_time <= hit_cooldown {
+                    continue;
+                }
+                if let Some(collision) = weapon_collider.collide(&mannequin.collider) {
+                    contacts.push((Target::Mannequin(i), collision));
+                }
+            }
+            for (i, fighter) in self.fighters().enumerate() {
+                if i == attacker
+                    || !fighter.is_alive()
+                    || fighter.is_invulnerable(self.game_time, &self.config.dash)
+                    || self.game_time - fighter.hit_time <= hit_cooldown
+                {
+                    continue;
+                }
+                if let Some(collision) = weapon_collider.collide(&fighter.collider()) {
+                    contacts.push((Target::Fighter(i), collision));
+                }
+            }
+
+            let fighter = self.fighter(attacker);
+            let strike = Strike {
+                attacker,
+                intent: swing.intent,
+                power: swing.power,
+                mass: fighter.weapon.mass,
+                velocity: fighter.velocity + fighter.weapon.velocity,
+                origin: fighter.position,
+                effects: self
+                    .config
+                    .armed(fighter.weapon.name.as_deref())
+                    .on_hit
+                    .clone(),
+            };
+            for (target, collision) in contacts {
+                self.resolve_hit(strike.clone(), target, collision);
+            }
+        }
+    }
+
+    /// Defeated opponents are gone, the player respawns after a delay.
+    pub(super) fn remove_defeated(&mut self) {
+        let alive: Vec<bool> = self
+            .opponents
+            .iter()
+            .map(|fighter| fighter.is_alive())
+            .collect();
+        if alive.contains(&false) {
+            // Projectiles refer to the fighters by index
+            let mut index = 0;
+            let new_index: Vec<Option<usize>> = std::iter::once(true)
+                .chain(alive)
+                .map(|alive| {
+                    alive.then(|| {
+                        index += 1;
+                        index - 1
+                    })
+                })
+                .collect();
+            // So do the effects, the ones from the defeated lose their source
+            let effects = self
+                .mannequins
+                .iter_mut()
+                .map(|mannequin| &mut mannequin.effects)
+                .chain(
+                    self.opponents
+                        .iter_mut()
+                        .map(|fighter| &mut fighter.effects),
+                )
+                .chain(std::iter::once(&mut self.player.effects));
+            for effects in effects {
+                for active in &mut effects.active {
+                    active.source = active.source.and_then(|source| new_index[source]);
+                }
+            }
+            self.projectiles
+                .retain_mut(|projectile| match new_index[projectile.owner] {
+                    Some(owner) => {
+                        projectile.owner = owner;
+                        true
+                    }
+                    None => false,
+                });
+            self.opponents.retain(Fighter::is_alive);
+        }
+
+        let player = &mut self.player;
+        match player.death_time {
+            None if !player.is_alive() => {
+                // Down until the respawn, ignoring the input
+                player.death_time = Some(self.game_time);
+                player.target_move_dir = vec2::ZERO;
+                player.cursor.state = CursorState::Idle;
+                player.effects = StatusEffects::default();
+                player.release_weapon();
+            }
+            Some(time) if self.game_time - time >= self.config.player.respawn_time => {
+                player.death_time = None;
+                let max = player.health.max();
+                player.health.set(max);
+                player.hit_time = self.game_time;
+            }
+            _ => {}
+        }
+    }
+
+    /// Count down the status effects and apply the bleeding.
+    pub(super) fn update_effects(&mut self, delta_time: Time) {
+        for mannequin in &mut self.mannequins {
+            let attacker = mannequin.effects.bleed_source();
+            let damage = mannequin.effects.update(delta_time);
+            if !mannequin.is_alive() || damage <= Hp::ZERO {
+                continue;
+            }
+            mannequin.health.change(-damage);
+            if !mannequin.health.is_above_min() {
+                mannequin.death_time = Some(self.game_time);
+                mannequin.effects = StatusEffects::default();
+                self.outcomes.push(Outcome::Killed {
+                    position: mannequin.collider.position,
+                    fighter: None,
+                    attacker,
+                });
+            }
+        }
+
+        let fighters = std::iter::once(&mut self.player).chain(&mut self.opponents);
+        for (i, fighter) in fighters.enumerate() {
+            let attacker = fighter.effects.bleed_source();
+            let damage = fighter.effects.update(delta_time);
+            if damage <= Hp::ZERO || !fighter.is_alive() {
+                continue;
+            }
+            fighter.health.change(-damage);
+            if !fighter.is_alive() {
+                self.outcomes.push(Outcome::Killed {
+                    position: fighter.position,
+                    fighter: Some(i),
+                    attacker,
+                });
+            }
+        }
+
+        // A stunned player drops the charge
+        let cursor = &mut self.player.cursor;
+        if self.player.effects.is_stunned() && cursor.state != CursorState::Idle {
+            cursor.state = CursorState::Idle;
+            cursor.last_state = CursorState::Idle;
+            cursor.state_start = self.game_time;
+            cursor.cancelled = true;
+        }
+    }
+
+    /// Damage of the strike based on its momentum relative to the target.
+    /// A slow glancing contact does little, a fast full swing hits hard.
+    fn impact_damage(&self, strike: &Strike, target: Target) -> Hp {
+        let target_velocity = match target {
+            Target::Mannequin(_) => vec2::ZERO,
+            Target::Fighter(i) => self.fighter(i).velocity,
+        };
+        let speed = (strike.velocity - target_velocity).len();
+        strike.mass * speed * strike.power * self.config.combat.damage_scale
+    }
+
+    pub(super) fn resolve_hit(&mut self, strike: Strike, target: Target, collision: Collision) {
+        let game_time = self.game_time;
+        let attacker = strike.attacker;
+        self.camera_controller
+            .add_trauma(strike.power * self.config.camera.hit_trauma);
+
+        // Fighters block attacks in front of them and are vulnerable from behind
+        let (blocked, backstab) = match target {
+            Target::Mannequin(_) => (false, false),
+            Target::Fighter(i) => {
+                let combat = &self.config.combat;
+                let attacker = strike.origin;
+                let defender = self.fighter(i);
+                let defending = matches!(
+                    defender.weapon.action,
+                    WeaponAction::Swing(WeaponSwing {
+                        intent: WeaponIntent::Defend,
+                        ..
+                    })
+                );
+                let blocked = defending && defender.is_facing(attacker, combat.block_angle);
+                let backstab = !defender.is_facing(attacker, r32(360.0) - combat.backstab_angle);
+                (blocked, backstab)
+            }
+        };
+
+        let mut damage = self.impact_damage(&strike, target) * self.fighter(attacker).stats.damage;
+        if backstab {
+            damage *= self.config.combat.backstab_multiplier;
+        }
+
+        let (health, effects, position) = match target {
+            Target::Mannequin(i) => {
+                let mannequin = &mut self.mannequins[i];
+                mannequin.hit_time = game_time;
+                (
+                    &mut mannequin.health,
+                    &mut mannequin.effects,
+                    mannequin.collider.position,
+                )
+            }
+            Target::Fighter(i) => {
+                let fighter = self.fighter_mut(i);
+                fighter.hit_time = game_time;
+                (&mut fighter.health, &mut fighter.effects, fighter.position)
+            }
+        };
+
+        match (strike.intent, blocked) {
+            (WeaponIntent::Defend, _) | (WeaponIntent::Attack, true) => {
+                self.outcomes.push(Outcome::Parried {
+                    attacker: strike.attacker,
+                    position: collision.point,
+                    normal: collision.normal,
+                    power: strike.power,
+                });
+            }
+            (WeaponIntent::Attack, false) => {
+                health.change(-damage);
+                let killed = !health.is_above_min();
+                let applied: Vec<StatusEffect> = strike
+                    .effects
+                    .iter()
+                    .filter(|config| !killed && strike.power >= config.min_power)
+                    .map(|config| {
+                        effects.apply(config.effect, config.duration, Some(strike.attacker));
+                        config.effect
+                    })
+                    .collect();
+                self.outcomes.push(Outcome::Hit {
+                    attacker: strike.attacker,
+                    position: collision.point,
+                    normal: collision.normal,
+                    power: strike.power,
+                    damage,
+                    backstab,
+                });
+
+                for effect in applied {
+                    self.outcomes
+                        .push(Outcome::EffectApplied { position, effect });
+                }
+
+                if killed {
+                    if let Target::Mannequin(i) = target {
+                        self.mannequins[i].death_time = Some(game_time);
+                    }
+                    let fighter = match target {
+                        Target::Mannequin(_) => None,
+                        Target::Fighter(i) => Some(i),
+                    };
+                    self.outcomes.push(Outcome::Killed {
+                        position,
+                        fighter,
+                        attacker: Some(strike.attacker),
+                    });
+                }
+
+                let combo_window = self.config.player.combo_window;
+                let combo = &mut self.fighter_mut(attacker).combo;
+                if combo.count > 0 && game_time - combo.last_hit <= combo_window {
+                    combo.count += 1;
+                    let count = combo.count;
+                    self.outcomes.push(Outcome::ComboStep {
+                        position: collision.point,
+                        count,
+                    });
+                } else {
+                    combo.count = 1;
+                }
+                self.fighter_mut(attacker).combo.last_hit = game_time;
+            }
+        }
+    }
+}
@@ -2,6 +2,13 @@ use super::*;
 
 impl Model {
     pub fn handle_event(&mut self, event: Event) {
+        if !self.player.is_alive() {
+            return;
+        }
+        // Stunned fighters can only aim
+        if self.player.effects.is_stunned() && !matches!(event, Event::CursorMove { .. }) {
+            return;
+        }
         match event {
             Event::CursorMove { delta } => {
                 let position = self.player.cursor.pos + delta;
@@ -18,6 +25,9 @@ impl Model {
                 });
             }
             Event::Charge(intent) => {
+                if self.player.cursor.cancelled {
+                    return;
+                }
                 self.player.cursor.state = match intent {
                     WeaponIntent::Attack => CursorState::Attack,
                     WeaponIntent::Defend => CursorState::Defend,
@@ -26,6 +36,9 @@ impl Model {
             Event::Release => {
                 self.player.cursor.state = CursorState::Idle;
             }
+            Event::Dash => self.dash(),
+            Event::Feint => self.feint(),
+            Event::Throw => self.throw(),
         }
     }
 }
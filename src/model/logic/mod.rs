@@ -1,27 +1,70 @@
+mod ai;
+mod camera;
+mod clash;
+mod combat;
 mod event;
+mod projectile;
 
 use super::*;
 
 impl Model {
+    /// Replace the config, updating all values that were copied from it.
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+        self.apply_config();
+    }
+
+    /// Update all values that were copied from the config.
+    pub fn apply_config(&mut self) {
+        let resolution = self.config.display.resolution.map(|x| x.max(1) as f32);
+        self.camera_controller.aspect = r32(resolution.x / resolution.y);
+
+        let config = &self.config;
+        for fighter in std::iter::once(&mut self.player).chain(&mut self.opponents) {
+            fighter
+                .weapon
+                .apply_config(config.armed(fighter.weapon.name.as_deref()));
+
+            // Keep the same fill when the maximum changes
+            let ratio = fighter.health.get_ratio();
+            fighter.health = Bounded::new_max(config.player.health * fighter.stats.health);
+            fighter.health.set_ratio(ratio);
+            let ratio = fighter.stamina.get_ratio();
+            fighter.stamina = Bounded::new_max(config.player.stamina);
+            fighter.stamina.set_ratio(ratio);
+        }
+    }
+
     pub fn update(&mut self, delta_time: Time) {
         self.real_time += delta_time;
         self.game_time += delta_time;
+        self.round_time += delta_time;
 
-        self.update_texts(delta_time);
-        self.update_cursor(delta_time);
-        self.control(delta_time);
-        self.update_player(delta_time);
-        self.update_weapon(delta_time);
+        // A player with a brain is controlled like the opponents
+        if self.player.brain.is_none() && self.player.is_alive() {
+            self.update_cursor(delta_time);
+            self.control(delta_time);
+        }
+        self.control_ai(delta_time);
+        self.update_fighters(delta_time);
+        self.update_weapons(delta_time);
+        self.weapon_clashes(delta_time);
         self.weapon_hits(delta_time);
+        self.update_projectiles(delta_time);
+        self.update_effects(delta_time);
+        self.remove_defeated();
+        self.update_mannequins(delta_time);
+        self.update_camera(delta_time);
     }
 
     fn update_cursor(&mut self, _delta_time: Time) {
         // Validate cursor state (in case some event is missed, e.g. when window loses focus)
-        let attack =
-            geng_utils::key::is_key_pressed(self.geng.window(), &self.config.controls.attack);
-        let defend =
-            geng_utils::key::is_key_pressed(self.geng.window(), &self.config.controls.defend);
+        let PlayerInput { attack, defend, .. } = self.input;
+        if self.player.cursor.cancelled && !self.player.effects.is_stunned() {
+            self.player.cursor.cancelled = attack || defend;
+        }
         match self.player.cursor.state {
+            CursorState::Idle if self.player.cursor.cancelled => {}
             CursorState::Idle => {
                 if attack {
                     self.player.cursor.state = CursorState::Attack;
@@ -44,6 +87,7 @@ impl Model {
         if self.player.cursor.state != self.player.cursor.last_state {
             self.check_action();
             self.player.cursor.last_state = self.player.cursor.state;
+            self.player.cursor.state_start = self.game_time;
         }
 
         self.player
@@ -54,7 +98,8 @@ impl Model {
 
     fn control(&mut self, _delta_time: Time) {
         // Update weapon action
-        if !self.player.weapon.action.swinging() {
+        let action = &self.player.weapon.action;
+        if !action.swinging() && !action.is_bound() {
             let start = self
                 .player
                 .cursor
@@ -64,10 +109,10 @@ impl Model {
                 .position(|entry| entry.state != self.player.cursor.last_state)
                 .map(|len| self.player.cursor.history.len() - len)
                 .unwrap_or(0);
-            let target = if let Some(start) = self.player.cursor.history.get(start) {
-                start.relative_pos
+            let (target, start_time) = if let Some(start) = self.player.cursor.history.get(start) {
+                (start.relative_pos, start.time)
             } else {
-                self.player.weapon.position
+                (self.player.weapon.position, self.game_time)
             };
             self.player.weapon.action = match self.player.cursor.state {
                 CursorState::Idle => WeaponAction::Idle {
@@ -76,49 +121,246 @@ impl Model {
                 CursorState::Attack => WeaponAction::Charging {
                     target,
                     intent: WeaponIntent::Attack,
+                    start_time,
                 },
                 CursorState::Defend => WeaponAction::Charging {
                     target,
                     intent: WeaponIntent::Defend,
+                    start_time,
                 },
             };
         }
 
-        let mut move_dir = vec2::<f32>::ZERO;
-        if geng_utils::key::is_key_pressed(self.geng.window(), &self.config.controls.up) {
-            move_dir.y += 1.0;
+        self.player.target_move_dir = self.input.move_dir;
+    }
+
+    fn update_fighters(&mut self, delta_time: Time) {
+        let config = &self.config;
+        let arena = self.level.arena;
+        for fighter in std::iter::once(&mut self.player).chain(&mut self.opponents) {
+            // Keep the burst velocity for the duration of the dash
+            if !fighter.is_dashing(self.game_time, &config.dash) {
+                let walk_speed = config.player.walk_speed * fighter.effects.slow_factor();
+                let target_velocity = fighter.target_move_dir * walk_speed;
+                fighter.velocity += (target_velocity - fighter.velocity)
+                    .clamp_len(..=config.player.acceleration * delta_time);
+                fighter.velocity = fighter.velocity.clamp_len(..=walk_speed);
+            }
+
+            fighter.position += fighter.velocity * delta_time;
+            if fighter.cursor.pos.len() > r32(0.1) {
+                fighter.facing = fighter.cursor.pos.normalize();
+            }
+            fighter
+                .stamina
+                .change(config.player.stamina_regen * delta_time);
+
+            // Stay inside the arena
+            let clamped = vec2(
+                fighter.position.x.clamp(arena.min.x, arena.max.x),
+                fighter.position.y.clamp(arena.min.y, arena.max.y),
+            );
+            if clamped.x != fighter.position.x {
+                fighter.velocity.x = Coord::ZERO;
+            }
+            if clamped.y != fighter.position.y {
+                fighter.velocity.y = Coord::ZERO;
+            }
+            fighter.position = clamped;
+        }
+    }
+
+    /// Start a dash of the player in the movement direction.
+    pub(super) fn dash(&mut self) {
+        let config = &self.config.dash;
+        let player = &mut self.player;
+        if player
+            .dash_time
+            .is_some_and(|time| self.game_time - time < config.cooldown)
+            || player.stamina.value() < config.stamina_cost
+        {
+            return;
         }
-        if geng_utils::key::is_key_pressed(self.geng.window(), &self.config.controls.down) {
-            move_dir.y -= 1.0;
+
+        // Dash where the player is going, or where they were going before stopping
+        let mut direction = player.target_move_dir.normalize_or_zero();
+        if direction == vec2::ZERO {
+            direction = player.velocity.normalize_or_zero();
         }
-        if geng_utils::key::is_key_pressed(self.geng.window(), &self.config.controls.left) {
-            move_dir.x -= 1.0;
+        if direction == vec2::ZERO {
+            return;
         }
-        if geng_utils::key::is_key_pressed(self.geng.window(), &self.config.controls.right) {
-            move_dir.x += 1.0;
+
+        player.velocity = direction * config.speed;
+        player.stamina.change(-config.stamina_cost);
+        player.dash_time = Some(self.game_time);
+        self.outcomes.push(Outcome::Dashed {
+            position: player.position,
+            direction,
+        });
+    }
+
+    /// Cancel the charge of the player without swinging.
+    pub(super) fn feint(&mut self) {
+        let cost = self.config.combat.feint_cost;
+        let player = &mut self.player;
+        if player.cursor.state == CursorState::Idle || player.stamina.value() < cost {
+            return;
         }
-        self.player.target_move_dir = move_dir.as_r32();
+
+        player.stamina.change(-cost);
+        player.cursor.state = CursorState::Idle;
+        player.cursor.last_state = CursorState::Idle;
+        player.cursor.state_start = self.game_time;
+        player.cursor.cancelled = true;
+        self.outcomes.push(Outcome::Feinted {
+            position: player.position + player.weapon.position,
+        });
     }
 
-    fn update_player(&mut self, delta_time: Time) {
-        let target_velocity = self.player.target_move_dir * self.config.player.walk_speed;
-        self.player.velocity += (target_velocity - self.player.velocity)
-            .clamp_len(..=self.config.player.acceleration * delta_time);
+    fn update_weapons(&mut self, delta_time: Time) {
+        let trail_time = self.config.cursor.trail_time;
+        for fighter in std::iter::once(&mut self.player).chain(&mut self.opponents) {
+            fighter.update_weapon(self.game_time, trail_time, delta_time);
+        }
+    }
 
-        self.player.position += self.player.velocity * delta_time;
+    /// The charge of the player, releasing it now would produce a swing of that power.
+    /// Returns `None` if the weapon is not being charged.
+    pub fn current_charge(&self) -> Option<Charge> {
+        let cursor = &self.player.cursor;
+        if cursor.state == CursorState::Idle {
+            return None;
+        }
+        let weapon = self.config.armed(self.player.weapon.name.as_deref());
+        Some(self.charge(weapon, self.game_time - cursor.state_start))
     }
 
-    fn update_weapon(&mut self, delta_time: Time) {
+    /// Charge of the weapon after holding the input for the given duration.
+    pub fn charge(&self, weapon: &WeaponConfig, duration: Time) -> Charge {
+        let config = &self.config.charge;
+        let t = (duration / config.full_time).clamp(R32::ZERO, R32::ONE);
+        let mut ratio = match config.curve {
+            ChargeCurve::Linear => t,
+            ChargeCurve::Sqrt => t.sqrt(),
+            ChargeCurve::EaseIn => t * t,
+        };
+
+        let state = if duration > config.full_time + config.hold_time {
+            ratio *= config.overcharge_power;
+            ChargeState::Overcharged
+        } else if t >= R32::ONE {
+            ChargeState::Full
+        } else {
+            ChargeState::Charging
+        };
+
+        // Not `clamp` since the tuning panel can temporarily invert the range
+        let power = weapon.power_min + ratio * (weapon.power_max - weapon.power_min);
+        Charge {
+            power: power.min(weapon.power_max).max(weapon.power_min),
+            state,
+        }
+    }
+
+    /// Find the latest continuous segment of the cursor history in the given state.
+    /// Returns its start, middle, and end entries.
+    fn gesture(&self, state: CursorState) -> Option<(CursorEntry, CursorEntry, CursorEntry)> {
+        let history = &self.player.cursor.history;
+        let end = history
+            .iter()
+            .rev()
+            .position(|entry| entry.state == state)?;
+
+        let start = history
+            .iter()
+            .rev()
+            .skip(end)
+            .position(|entry| entry.state != state)
+            .map(|len| history.len() - end - len)
+            .unwrap_or(0);
+        let end = history.len() - 1 - end;
+
+        Some((history[start], history[(start + end) / 2], history[end]))
+    }
+
+    pub(super) fn check_action(&mut self) {
+        let Some((start, mid, end)) = self.gesture(self.player.cursor.last_state) else {
+            return;
+        };
+
+        let pos = self.player.position + (start.relative_pos + end.relative_pos) / r32(2.0);
+        let duration = self.game_time - self.player.cursor.state_start;
+        let weapon = self.config.armed(self.player.weapon.name.as_deref());
+        let power = self.charge(weapon, duration).power;
+
+        let (intent, text) = match end.state {
+            CursorState::Idle => return,
+            CursorState::Attack => (WeaponIntent::Attack, "Slash"),
+            CursorState::Defend => (WeaponIntent::Defend, "Parry"),
+        };
+
+        log::debug!(
+            "{} at ({:.02}, {:.02}), power: {:.02}",
+            text,
+            pos.x,
+            pos.y,
+            power
+        );
+
         let weapon = &mut self.player.weapon;
+        let arc = Parabola::new([start.relative_pos, mid.relative_pos, end.relative_pos]);
+        weapon.action = WeaponAction::Swing(WeaponSwing {
+            intent,
+            start_time: self.game_time,
+            power,
+            arc,
+        });
+        // Boost
+        let t = arc.project(weapon.position);
+        let projection = arc.get(t);
+        let tangent = arc.tangent(t);
+        let normal = projection - weapon.position;
+        let boost =
+            (normal * r32(3.0) + (tangent.normalize_or_zero() * r32(5.0) * power)) * r32(3.0);
+        weapon.velocity = (weapon.velocity + boost).clamp_len(..=weapon.speed_max);
+        self.outcomes.push(Outcome::SwingStarted {
+            fighter: 0,
+            position: self.player.position + weapon.position,
+            stroke_center: pos,
+            intent,
+            power,
+            speed: weapon.velocity.len(),
+            arc_length: r32(arc.map(R32::as_f32).chain(16).length()),
+        });
+    }
+
+    fn update_mannequins(&mut self, _delta_time: Time) {
+        for mannequin in &mut self.mannequins {
+            if let Some(death_time) = mannequin.death_time {
+                if self.game_time - death_time >= self.config.mannequin.respawn_time {
+                    mannequin.death_time = None;
+                    mannequin.health = Bounded::new_max(self.config.mannequin.health);
+                }
+            }
+        }
+    }
+}
+
+impl Fighter {
+    fn update_weapon(&mut self, game_time: Time, trail_time: Time, delta_time: Time) {
+        let slow = self.effects.slow_factor();
+        let weapon = &mut self.weapon;
+        let acceleration = weapon.acceleration * slow;
         match &weapon.action {
             WeaponAction::Swing(swing) => {
                 let t = swing.arc.project(weapon.position);
                 if t > R32::ONE {
                     // Motion finished - boost backwards
-                    let boost = (self.player.cursor.pos - weapon.position) * r32(5.0) * swing.power;
+                    let boost = (self.cursor.pos - weapon.position) * r32(5.0) * swing.power;
                     weapon.velocity = (weapon.velocity + boost).clamp_len(..=weapon.speed_max);
                     weapon.action = WeaponAction::Idle {
-                        target: self.player.cursor.pos,
+                        target: self.cursor.pos,
                     };
                 } else {
                     let projection = swing.arc.get(t);
@@ -126,7 +368,7 @@ impl Model {
                     let normal = projection - weapon.position;
                     let normal = normal * normal.len();
 
-                    let acceleration = weapon.acceleration * weapon.swing_boost * swing.power;
+                    let acceleration = acceleration * weapon.swing_boost * swing.power;
 
                     let target_vel =
                         (normal + (tangent.normalize_or_zero())) * acceleration / r32(10.0);
@@ -141,14 +383,17 @@ impl Model {
                 let target_vel =
                     ((target - weapon.position) * r32(10.0)).clamp_len(..=weapon.speed_max);
                 weapon.velocity +=
-                    (target_vel - weapon.velocity).clamp_len(..=weapon.acceleration * delta_time);
+                    (target_vel - weapon.velocity).clamp_len(..=acceleration * delta_time);
             }
-            WeaponAction::Charging { target, intent } => {
+            WeaponAction::Charging { target, .. } => {
                 let target = target.clamp_len(..=weapon.reach);
                 let target_vel =
                     ((target - weapon.position) * r32(10.0)).clamp_len(..=weapon.speed_max);
                 weapon.velocity +=
-                    (target_vel - weapon.velocity).clamp_len(..=weapon.acceleration * delta_time);
+                    (target_vel - weapon.velocity).clamp_len(..=acceleration * delta_time);
+            }
+            WeaponAction::Bind { .. } => {
+                weapon.velocity = vec2::ZERO;
             }
         }
         weapon.position =
@@ -156,11 +401,11 @@ impl Model {
 
         weapon
             .history
-            .retain(|entry| self.game_time - entry.time < self.config.cursor.trail_time);
+            .retain(|entry| game_time - entry.time < trail_time);
         weapon.history.push_back(CursorEntry {
-            world_pos: self.player.position + weapon.position,
+            world_pos: self.position + weapon.position,
             relative_pos: weapon.position,
-            time: self.game_time,
+            time: game_time,
             state: match &weapon.action {
                 WeaponAction::Swing(WeaponSwing { intent, .. }) => match intent {
                     WeaponIntent::Attack => CursorState::Attack,
@@ -170,107 +415,4 @@ impl Model {
             },
         });
     }
-
-    pub(super) fn check_action(&mut self) {
-        let cursor = &mut self.player.cursor;
-        let Some(end) = cursor
-            .history
-            .iter()
-            .rev()
-            .position(|entry| entry.state == cursor.last_state)
-        else {
-            return;
-        };
-
-        let start = cursor
-            .history
-            .iter()
-            .rev()
-            .skip(end)
-            .position(|entry| entry.state != cursor.last_state)
-            .map(|len| cursor.history.len() - end - len)
-            .unwrap_or(0);
-        let end = cursor.history.len() - 1 - end;
-
-        let mid = cursor.history[(start + end) / 2];
-        let start = cursor.history[start];
-        let end = cursor.history[end];
-
-        let pos = self.player.position + (start.relative_pos + end.relative_pos) / r32(2.0);
-
-        let time = (end.time - start.time) / self.config.cursor.trail_time;
-
-        let power_t = time; // Maybe sqrt
-        let power = power_t * (self.config.weapon.power_max - self.config.weapon.power_min)
-            + self.config.weapon.power_min;
-
-        let (intent, text) = match end.state {
-            CursorState::Idle => return,
-            CursorState::Attack => (WeaponIntent::Attack, "Slash"),
-            CursorState::Defend => (WeaponIntent::Defend, "Parry"),
-        };
-
-        log::debug!(
-            "{} at ({:.02}, {:.02}), power: {:.02}",
-            text,
-            pos.x,
-            pos.y,
-            power
-        );
-
-        let weapon = &mut self.player.weapon;
-        let arc = Parabola::new([start.relative_pos, mid.relative_pos, end.relative_pos]);
-        weapon.action = WeaponAction::Swing(WeaponSwing { intent, power, arc });
-        // Boost
-        let t = arc.project(weapon.position);
-        let projection = arc.get(t);
-        let tangent = arc.tangent(t);
-        let normal = projection - weapon.position;
-        let boost =
-            (normal * r32(3.0) + (tangent.normalize_or_zero() * r32(5.0) * power)) * r32(3.0);
-        weapon.velocity = (weapon.velocity + boost).clamp_len(..=weapon.speed_max);
-
-        let degrees = r32(thread_rng().gen_range(-15.0..=15.0));
-        self.floating_texts.push(FloatingText {
-            text: format!("{} {}", text, power.round()),
-            pos,
-            lifetime: Bounded::new_max(r32(0.5)),
-            initial_scale: r32(1.0),
-            rotation: Angle::from_degrees(degrees),
-        });
-    }
-
-    fn weapon_hits(&mut self, _delta_time: Time) {
-        let player = &mut self.player;
-
-        // TODO: Sword hitbox
-        let weapon_collider = Collider::circle(player.position + player.weapon.position, r32(0.1));
-
-        if let WeaponAction::Swing(swing) = &player.weapon.action {
-            for mannequin in &mut self.mannequins {
-                if self.game_time - mannequin.hit_time > r32(0.5)
-                    && weapon_collider.check(&mannequin.collider)
-                {
-                    mannequin.hit_time = self.game_time;
-
-                    let degrees = r32(thread_rng().gen_range(-15.0..=15.0));
-                    self.floating_texts.push(FloatingText {
-                        text: format!("{}", (swing.power.as_f32() * 10.0).round() as i64),
-                        pos: weapon_collider.position,
-                        lifetime: Bounded::new_max(r32(1.0)),
-                        initial_scale: r32(1.0),
-                        rotation: Angle::from_degrees(degrees),
-                    });
-                }
-            }
-        }
-    }
-
-    fn update_texts(&mut self, delta_time: Time) {
-        for text in &mut self.floating_texts {
-            text.lifetime.change(-delta_time);
-        }
-        self.floating_texts
-            .retain(|text| text.lifetime.is_above_min());
-    }
 }
@@ -0,0 +1,126 @@
+use super::{
+    combat::{Strike, Target},
+    *,
+};
+
+impl Model {
+    /// Throw a projectile the way the cursor was flicked.
+    pub(super) fn throw(&mut self) {
+        let config = &self.config.throw;
+        let player = &self.player;
+        if player
+            .throw_time
+            .is_some_and(|time| self.game_time - time < config.cooldown)
+            || player.stamina.value() < config.stamina_cost
+        {
+            return;
+        }
+
+        // Cursor velocity over the latest part of the history
+        let recent: Vec<&CursorEntry> = player
+            .cursor
+            .history
+            .iter()
+            .filter(|entry| self.game_time - entry.time <= config.gesture_time)
+            .collect();
+        let flick = match (recent.first(), recent.last()) {
+            (Some(first), Some(last)) if last.time > first.time => {
+                (last.relative_pos - first.relative_pos) / (last.time - first.time)
+            }
+            _ => vec2::ZERO,
+        };
+
+        // Without a flick, toss it towards the cursor
+        let mut direction = flick.normalize_or_zero();
+        if direction == vec2::ZERO {
+            direction = player.cursor.pos.normalize_or_zero();
+        }
+        if direction == vec2::ZERO {
+            direction = player.facing;
+        }
+        let speed = (flick.len() * config.speed_scale).clamp(config.speed_min, config.speed_max);
+
+        let kind = config.projectile;
+        let projectile = self.config.projectiles.get(kind);
+        let position = player.position + direction * (r32(0.4) + projectile.radius);
+        let velocity = player.velocity + direction * speed;
+        self.projectiles.push(Projectile {
+            kind,
+            owner: 0,
+            collider: Collider::circle(position, projectile.radius),
+            velocity,
+            lifetime: projectile.lifetime,
+        });
+        self.outcomes.push(Outcome::Thrown { position, velocity });
+
+        let stamina_cost = config.stamina_cost;
+        self.player.stamina.change(-stamina_cost);
+        self.player.throw_time = Some(self.game_time);
+    }
+
+    /// Move the projectiles and resolve their hits against the targets and the walls.
+    pub(super) fn update_projectiles(&mut self, delta_time: Time) {
+        let arena = self.level.arena;
+        for mut projectile in std::mem::take(&mut self.projectiles) {
+            projectile.lifetime -= delta_time;
+            if projectile.lifetime <= Time::ZERO {
+                continue;
+            }
+            projectile.collider.position += projectile.velocity * delta_time;
+
+            let position = projectile.collider.position;
+            if !arena.contains(position) {
+                let clamped = vec2(
+                    position.x.clamp(arena.min.x, arena.max.x),
+                    position.y.clamp(arena.min.y, arena.max.y),
+                );
+                self.outcomes.push(Outcome::WallHit {
+                    position: clamped,
+                    normal: (position - clamped).normalize_or_zero(),
+                });
+                continue;
+            }
+
+            let mut target = self
+                .mannequins
+                .iter()
+                .enumerate()
+                .filter(|(_, mannequin)| mannequin.is_alive())
+                .find_map(|(i, mannequin)| {
+                    let collision = projectile.collider.collide(&mannequin.collider)?;
+                    Some((Target::Mannequin(i), collision))
+                });
+            if target.is_none() {
+                target = self
+                    .fighters()
+                    .enumerate()
+                    .filter(|&(i, fighter)| {
+                        i != projectile.owner
+                            && fighter.is_alive()
+                            && !fighter.is_invulnerable(self.game_time, &self.config.dash)
+                    })
+                    .find_map(|(i, fighter)| {
+                        let collision = projectile.collider.collide(&fighter.collider())?;
+                        Some((Target::Fighter(i), collision))
+                    });
+            }
+
+            match target {
+                Some((target, collision)) => {
+                    let config = self.config.projectiles.get(projectile.kind);
+                    let strike = Strike {
+                        attacker: projectile.owner,
+                        intent: WeaponIntent::Attack,
+                        power: R32::ONE,
+                        mass: config.mass,
+                        velocity: projectile.velocity,
+                        origin: position - projectile.velocity,
+                        effects: config.on_hit.clone(),
+                    };
+                    self.resolve_hit(strike, target, collision);
+                }
+                None => self.projectiles.push(projectile),
+            }
+        }
+    }
+}
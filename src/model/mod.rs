@@ -1,30 +1,82 @@
+mod brain;
 mod collider;
+mod effects;
 mod event;
 mod logic;
+mod outcome;
 
-pub use self::{collider::*, event::Event};
+pub use self::{brain::*, collider::*, effects::*, event::Event, outcome::Outcome};
 
 use crate::{prelude::*, util::parabola::Parabola};
 
 pub struct Model {
-    pub geng: Geng, // TODO: Somehow not require the engine ref (used for keys pressed)
     pub config: Config,
+    pub level: Level,
 
     pub camera: Camera2d,
+    pub camera_controller: CameraController,
     pub real_time: Time,
     pub game_time: Time,
+    /// Time since the start of the round.
+    pub round_time: Time,
 
-    pub player: Player,
+    pub player: Fighter,
+    /// Fighters opposing the player.
+    pub opponents: Vec<Fighter>,
     pub mannequins: Vec<Mannequin>,
+    pub projectiles: Vec<Projectile>,
 
-    pub floating_texts: Vec<FloatingText>,
+    /// Inputs of the player held down, set by the game before every update.
+    pub input: PlayerInput,
+    /// Outcomes produced since they were last drained.
+    pub outcomes: Vec<Outcome>,
+    /// Randomness of the model, seeded so that the same inputs give the same results.
+    pub rng: StdRng,
+}
+
+/// Held down inputs of the player, the model itself runs without a window.
+#[derive(Debug, Clone)]
+pub struct PlayerInput {
+    pub attack: bool,
+    pub defend: bool,
+    /// Movement direction with the components in range `-1..=1`.
+    pub move_dir: vec2<Coord>,
+}
+
+/// Moves the camera, the result is written into [Model::camera].
+#[derive(Debug, Clone)]
+pub struct CameraController {
+    /// Camera position without the shake.
+    pub center: vec2<Coord>,
+    /// Vertical field of view without the shake.
+    pub fov: Coord,
+    /// Width to height ratio of the view.
+    pub aspect: R32,
+    /// Intensity of the screen shake in range `0..=1`.
+    pub trauma: R32,
 }
 
 #[derive(Debug, Clone)]
 pub struct Mannequin {
     pub collider: Collider,
+    pub health: Bounded<Hp>,
     /// Last time the mannequin got hit.
     pub hit_time: Time,
+    /// Time when the mannequin was destroyed, it is rebuilt after a delay.
+    pub death_time: Option<Time>,
+    pub effects: StatusEffects,
+}
+
+/// A thrown weapon in flight.
+#[derive(Debug, Clone)]
+pub struct Projectile {
+    pub kind: ProjectileKind,
+    /// Index into [Model::fighters] of the thrower.
+    pub owner: usize,
+    pub collider: Collider,
+    pub velocity: vec2<Coord>,
+    /// Time left until the projectile disappears.
+    pub lifetime: Time,
 }
 
 #[derive(Debug, Clone)]
@@ -35,30 +87,81 @@ pub struct Cursor {
     pub state: CursorState,
     /// State on the previous frame.
     pub last_state: CursorState,
+    /// Time when the current state was entered.
+    pub state_start: Time,
+    /// The charge was cancelled by a feint, held inputs are ignored until released.
+    pub cancelled: bool,
 }
 
+/// An entity wielding a weapon, either the player or an opponent.
 #[derive(Debug, Clone)]
-pub struct Player {
-    /// Cursor relative to the player position.
+pub struct Fighter {
+    /// Cursor relative to the fighter position.
     pub cursor: Cursor,
     pub position: vec2<Coord>,
     pub velocity: vec2<Coord>,
     pub target_move_dir: vec2<Coord>,
+    /// Direction the fighter is looking at, follows the cursor.
+    pub facing: vec2<Coord>,
+    pub health: Bounded<Hp>,
+    pub stamina: Bounded<R32>,
+    pub stats: FighterStats,
     pub weapon: WeaponControl,
+    pub combo: Combo,
+    /// Last time the fighter got hit.
+    pub hit_time: Time,
+    pub effects: StatusEffects,
+    /// Computer control, `None` for the fighters controlled by the input.
+    pub brain: Option<Brain>,
+    /// Time when the last dash started.
+    pub dash_time: Option<Time>,
+    /// Time of the last throw.
+    pub throw_time: Option<Time>,
+    /// Time when the player died, they get back up after a delay.
+    pub death_time: Option<Time>,
+}
+
+/// Multipliers of the base stats from the config.
+#[derive(Debug, Clone, Copy)]
+pub struct FighterStats {
+    pub health: R32,
+    pub damage: R32,
+}
+
+impl Default for FighterStats {
+    fn default() -> Self {
+        Self {
+            health: R32::ONE,
+            damage: R32::ONE,
+        }
+    }
+}
+
+/// Consecutive hits landed without long pauses.
+#[derive(Debug, Clone)]
+pub struct Combo {
+    pub count: usize,
+    /// Time of the last hit in the combo.
+    pub last_hit: Time,
 }
 
 #[derive(Debug, Clone)]
 pub struct WeaponControl {
+    /// Name of the weapon from [Config::arsenal], `None` for [Config::weapon].
+    pub name: Option<String>,
     pub history: VecDeque<CursorEntry>,
     pub reach: Coord,
     pub acceleration: Coord,
     pub swing_boost: Coord,
     pub speed_max: Coord,
+    pub mass: R32,
     /// Relative position of the weapon tip.
     pub position: vec2<Coord>,
     /// Relative velocity of the weapon tip.
     pub velocity: vec2<Coord>,
     pub action: WeaponAction,
+    /// Last time the weapon clashed with another one.
+    pub clash_time: Option<Time>,
 }
 
 #[derive(Debug, Clone)]
@@ -69,38 +172,61 @@ pub enum WeaponAction {
     Charging {
         target: vec2<Coord>,
         intent: WeaponIntent,
+        /// Time when the charge started.
+        start_time: Time,
     },
     Swing(WeaponSwing),
+    /// Locked against the weapon of another fighter until either side disengages.
+    Bind {
+        /// Index into [Model::fighters] of the other side of the bind.
+        partner: usize,
+        /// Time when the bind started.
+        start_time: Time,
+    },
 }
 
 impl WeaponAction {
     pub fn swinging(&self) -> bool {
         matches!(self, Self::Swing(_))
     }
+
+    pub fn is_bound(&self) -> bool {
+        matches!(self, Self::Bind { .. })
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct WeaponSwing {
     pub intent: WeaponIntent,
+    /// Time when the swing started.
+    pub start_time: Time,
     pub power: R32,
     pub arc: Parabola<Coord>,
 }
 
+/// Progress of holding the attack or the defend input.
+#[derive(Debug, Clone, Copy)]
+pub struct Charge {
+    /// Power of the swing if released now.
+    pub power: R32,
+    pub state: ChargeState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChargeState {
+    Charging,
+    /// Reached the maximum power.
+    Full,
+    /// Held for too long, the power has dropped.
+    Overcharged,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum WeaponIntent {
     Attack,
     Defend,
 }
 
-#[derive(Debug, Clone)]
-pub struct FloatingText {
-    pub text: String,
-    pub pos: vec2<Coord>,
-    pub lifetime: Bounded<Time>,
-    pub initial_scale: Coord,
-    pub rotation: Angle<Coord>,
-}
-
 #[derive(Debug, Clone, Copy)]
 pub struct CursorEntry {
     /// World position of the cursor.
@@ -120,47 +246,165 @@ pub enum CursorState {
 }
 
 impl Model {
-    pub fn new(geng: &Geng, config: Config) -> Self {
-        Self {
-            geng: geng.clone(),
-
+    pub fn new(config: Config, level: Level) -> Self {
+        let mut model = Self {
             camera: Camera2d {
                 center: vec2::ZERO,
                 rotation: Angle::ZERO,
-                fov: 10.0,
+                fov: config.camera.fov_min.as_f32(),
+            },
+            camera_controller: CameraController {
+                center: vec2::ZERO,
+                fov: config.camera.fov_min,
+                aspect: R32::ONE,
+                trauma: R32::ZERO,
             },
             real_time: Time::ZERO,
             game_time: Time::ZERO,
+            round_time: Time::ZERO,
 
-            player: Player {
-                cursor: Cursor {
-                    pos: vec2::ZERO,
-                    history: VecDeque::new(),
-                    state: CursorState::Idle,
-                    last_state: CursorState::Idle,
-                },
-                position: vec2::ZERO,
-                velocity: vec2::ZERO,
-                target_move_dir: vec2::ZERO,
-                weapon: WeaponControl {
-                    history: VecDeque::new(),
-                    acceleration: config.weapon.acceleration,
-                    swing_boost: config.weapon.swing_boost,
-                    speed_max: config.weapon.speed_max,
-                    reach: r32(2.0),
-                    position: vec2::ZERO,
-                    velocity: vec2::ZERO,
-                    action: WeaponAction::Idle { target: vec2::ZERO },
-                },
-            },
+            player: Fighter::new(vec2::ZERO, &config),
+            opponents: Vec::new(),
             mannequins: vec![Mannequin {
                 collider: Collider::aabb(Aabb2::point(vec2(3.0, 2.0)).extend_uniform(0.3).map(r32)),
+                health: Bounded::new_max(config.mannequin.health),
                 hit_time: Time::ZERO,
+                death_time: None,
+                effects: StatusEffects::default(),
             }],
+            projectiles: Vec::new(),
 
-            floating_texts: Vec::new(),
+            input: PlayerInput {
+                attack: false,
+                defend: false,
+                move_dir: vec2::ZERO,
+            },
+            outcomes: Vec::new(),
+            rng: StdRng::seed_from_u64(0),
 
             config,
+            level,
+        };
+        for spawn in model.level.opponents.clone() {
+            model.spawn_opponent(spawn.position, &spawn.archetype, FighterStats::default());
+        }
+        model.apply_config();
+        model
+    }
+}
+
+impl Mannequin {
+    pub fn is_alive(&self) -> bool {
+        self.death_time.is_none()
+    }
+}
+
+impl Fighter {
+    pub fn new(position: vec2<Coord>, config: &Config) -> Self {
+        Self {
+            cursor: Cursor {
+                pos: vec2::ZERO,
+                history: VecDeque::new(),
+                state: CursorState::Idle,
+                last_state: CursorState::Idle,
+                state_start: Time::ZERO,
+                cancelled: false,
+            },
+            position,
+            velocity: vec2::ZERO,
+            target_move_dir: vec2::ZERO,
+            facing: vec2(Coord::ONE, Coord::ZERO),
+            health: Bounded::new_max(config.player.health),
+            stamina: Bounded::new_max(config.player.stamina),
+            stats: FighterStats::default(),
+            weapon: WeaponControl::new(&config.weapon),
+            combo: Combo {
+                count: 0,
+                last_hit: Time::ZERO,
+            },
+            hit_time: Time::ZERO,
+            effects: StatusEffects::default(),
+            brain: None,
+            dash_time: None,
+            throw_time: None,
+            death_time: None,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.health.is_above_min()
+    }
+
+    /// Whether the fighter is in the middle of a dash.
+    pub fn is_dashing(&self, game_time: Time, config: &DashConfig) -> bool {
+        self.dash_time
+            .is_some_and(|time| game_time - time < config.duration)
+    }
+
+    /// Whether weapons pass through the fighter without hitting.
+    pub fn is_invulnerable(&self, game_time: Time, config: &DashConfig) -> bool {
+        self.dash_time
+            .is_some_and(|time| game_time - time < config.invulnerability)
+    }
+
+    /// Whether the point lies inside the cone of the given width (in degrees)
+    /// around the facing direction.
+    pub fn is_facing(&self, point: vec2<Coord>, cone: R32) -> bool {
+        let direction = (point - self.position).normalize_or_zero();
+        if direction == vec2::ZERO {
+            return true;
         }
+        let cos = vec2::dot(direction, self.facing).as_f32().clamp(-1.0, 1.0);
+        cos.acos().to_degrees() <= cone.as_f32() / 2.0
+    }
+
+    /// Collider of the body in world coordinates.
+    pub fn collider(&self) -> Collider {
+        Collider::circle(self.position, r32(0.4))
+    }
+
+    /// Collider of the whole blade, from the hand to the tip, in world coordinates.
+    pub fn blade_collider(&self) -> Collider {
+        let length = self.weapon.position.len().max(r32(0.1));
+        let mut collider = Collider::new(
+            self.position + self.weapon.position / r32(2.0),
+            Shape::rectangle(vec2(length, r32(0.1))),
+        );
+        collider.rotation = self.weapon.position.arg();
+        collider
+    }
+
+    /// Collider of the weapon tip in world coordinates.
+    pub fn weapon_collider(&self) -> Collider {
+        // TODO: Sword hitbox
+        Collider::circle(self.position + self.weapon.position, r32(0.1))
+    }
+}
+
+impl WeaponControl {
+    pub fn new(config: &WeaponConfig) -> Self {
+        let mut weapon = Self {
+            name: None,
+            history: VecDeque::new(),
+            reach: r32(2.0),
+            acceleration: Coord::ZERO,
+            swing_boost: Coord::ZERO,
+            speed_max: Coord::ZERO,
+            mass: R32::ONE,
+            position: vec2::ZERO,
+            velocity: vec2::ZERO,
+            action: WeaponAction::Idle { target: vec2::ZERO },
+            clash_time: None,
+        };
+        weapon.apply_config(config);
+        weapon
+    }
+
+    /// Update the values copied from the config.
+    pub fn apply_config(&mut self, config: &WeaponConfig) {
+        self.acceleration = config.acceleration;
+        self.swing_boost = config.swing_boost;
+        self.speed_max = config.speed_max;
+        self.mass = config.mass;
     }
 }
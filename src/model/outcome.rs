@@ -0,0 +1,93 @@
+use super::*;
+
+/// Outcome of the simulation, reported to the presentation layer
+/// (render, audio, stats) through [Model::outcomes].
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    /// A swing has been committed.
+    SwingStarted {
+        /// Index into [Model::fighters] of the swinging fighter.
+        fighter: usize,
+        /// World position of the weapon.
+        position: vec2<Coord>,
+        /// World position of the middle of the cursor stroke.
+        stroke_center: vec2<Coord>,
+        intent: WeaponIntent,
+        power: R32,
+        /// Speed of the weapon after the initial boost.
+        speed: Coord,
+        /// Length of the swing path.
+        arc_length: Coord,
+    },
+    /// An attack landed on a target.
+    Hit {
+        /// Index into [Model::fighters] of the attacker.
+        attacker: usize,
+        /// World position of the contact.
+        position: vec2<Coord>,
+        /// Direction of the impact, pointing into the target.
+        normal: vec2<Coord>,
+        power: R32,
+        damage: Hp,
+        /// The target was hit from behind.
+        backstab: bool,
+    },
+    /// A defensive swing made contact, or an attack was blocked.
+    Parried {
+        /// Index into [Model::fighters] of the fighter whose swing made the contact.
+        attacker: usize,
+        /// World position of the contact.
+        position: vec2<Coord>,
+        /// Direction of the impact, pointing into the target.
+        normal: vec2<Coord>,
+        power: R32,
+    },
+    /// Two weapons collided.
+    Clash {
+        /// World position of the contact.
+        position: vec2<Coord>,
+        /// Direction from the first weapon towards the second one.
+        normal: vec2<Coord>,
+        /// Speed at which the blades approached each other.
+        speed: Coord,
+        /// The weapons locked in a bind instead of bouncing.
+        bound: bool,
+    },
+    /// A charge was cancelled without swinging.
+    Feinted { position: vec2<Coord> },
+    /// A projectile was thrown.
+    Thrown {
+        position: vec2<Coord>,
+        velocity: vec2<Coord>,
+    },
+    /// A projectile hit the arena wall.
+    WallHit {
+        position: vec2<Coord>,
+        /// Direction into the wall.
+        normal: vec2<Coord>,
+    },
+    /// A status effect started on a target.
+    EffectApplied {
+        position: vec2<Coord>,
+        effect: StatusEffect,
+    },
+    /// A fighter started a dash.
+    Dashed {
+        position: vec2<Coord>,
+        direction: vec2<Coord>,
+    },
+    /// A target ran out of health.
+    Killed {
+        position: vec2<Coord>,
+        /// Index into [Model::fighters] of the killed fighter, `None` for the mannequins.
+        fighter: Option<usize>,
+        /// Index into [Model::fighters] of the killer, `None` if unknown.
+        attacker: Option<usize>,
+    },
+    /// A hit continued the combo.
+    ComboStep {
+        position: vec2<Coord>,
+        /// Number of hits in the combo so far.
+        count: usize,
+    },
+}
@@ -1,5 +1,5 @@
-pub use geng::{prelude::*, Key, MouseButton};
-pub use geng_utils::{bounded::Bounded, conversions::*, key::EventKey};
+pub use geng::prelude::*;
+pub use geng_utils::{bounded::Bounded, conversions::*};
 
 pub use std::collections::VecDeque;
 
@@ -8,3 +8,4 @@ pub use crate::{assets::*, model::*};
 pub type Color = Rgba<f32>;
 pub type Time = R32;
 pub type Coord = R32;
+pub type Hp = R32;
@@ -0,0 +1,87 @@
+use super::*;
+
+/// Speed above which the entity is considered walking.
+const WALK_THRESHOLD: f32 = 0.1;
+/// How long the hurt clip is shown after a hit.
+const HURT_DURATION: f32 = 0.5;
+
+/// Returns the animation the fighter should play and for how long it has been playing.
+pub fn fighter_animation(model: &Model, fighter: &Fighter) -> (AnimationState, Time) {
+    if let Some(death_time) = fighter.death_time {
+        return (AnimationState::Death, model.game_time - death_time);
+    }
+    let since_hit = model.game_time - fighter.hit_time;
+    if fighter.hit_time > Time::ZERO && since_hit.as_f32() < HURT_DURATION {
+        return (AnimationState::Hurt, since_hit);
+    }
+    match &fighter.weapon.action {
+        WeaponAction::Swing(swing) => (AnimationState::Swing, model.game_time - swing.start_time),
+        WeaponAction::Charging { start_time, .. } => {
+            (AnimationState::Windup, model.game_time - *start_time)
+        }
+        // Straining against the other blade
+        WeaponAction::Bind { start_time, .. } => {
+            (AnimationState::Windup, model.game_time - *start_time)
+        }
+        WeaponAction::Idle { .. } => {
+            if fighter.velocity.len().as_f32() > WALK_THRESHOLD {
+                (AnimationState::Walk, model.game_time)
+            } else {
+                (AnimationState::Idle, model.game_time)
+            }
+        }
+    }
+}
+
+pub fn weapon_animation(model: &Model, weapon: &WeaponControl) -> (AnimationState, Time) {
+    match &weapon.action {
+        WeaponAction::Swing(swing) => (AnimationState::Swing, model.game_time - swing.start_time),
+        WeaponAction::Charging { start_time, .. } => {
+            (AnimationState::Windup, model.game_time - *start_time)
+        }
+        WeaponAction::Bind { start_time, .. } => {
+            (AnimationState::Windup, model.game_time - *start_time)
+        }
+        WeaponAction::Idle { .. } => (AnimationState::Idle, model.game_time),
+    }
+}
+
+pub fn mannequin_animation(model: &Model, mannequin: &Mannequin) -> (AnimationState, Time) {
+    let since_hit = model.game_time - mannequin.hit_time;
+    if mannequin.hit_time > Time::ZERO && since_hit.as_f32() < HURT_DURATION {
+        (AnimationState::Hurt, since_hit)
+    } else {
+        (AnimationState::Idle, model.game_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fighter_animation_follows_the_state() {
+        let level = Level {
+            arena: Aabb2::ZERO.extend_symmetric(vec2(8.0, 5.0).as_r32()),
+            opponents: Vec::new(),
+            spawn_points: Vec::new(),
+        };
+        let mut model = Model::new(Config::default(), level);
+        model.game_time = r32(10.0);
+        let mut fighter = model.player.clone();
+        assert_eq!(
+            fighter_animation(&model, &fighter),
+            (AnimationState::Idle, r32(10.0))
+        );
+
+        fighter.hit_time = r32(9.8);
+        assert_eq!(fighter_animation(&model, &fighter).0, AnimationState::Hurt);
+
+        // Death is shown over the hit that caused it
+        fighter.death_time = Some(r32(9.5));
+        assert_eq!(
+            fighter_animation(&model, &fighter),
+            (AnimationState::Death, r32(0.5))
+        );
+    }
+}
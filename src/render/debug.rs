@@ -0,0 +1,154 @@
+use super::*;
+
+/// Which debug layers to draw.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebugLayers {
+    /// The arc of the active swing.
+    pub arc: bool,
+    /// The projection of the weapon onto the swing arc, with its tangent and normal.
+    pub projection: bool,
+    /// The circle limiting the weapon reach.
+    pub reach: bool,
+    /// Outlines of all colliders.
+    pub colliders: bool,
+}
+
+pub struct DebugRender {
+    geng: Geng,
+}
+
+impl DebugRender {
+    pub fn new(geng: &Geng) -> Self {
+        Self { geng: geng.clone() }
+    }
+
+    pub fn draw(&self, model: &Model, layers: &DebugLayers, framebuffer: &mut ugli::Framebuffer) {
+        let camera = &model.camera;
+        let player = &model.player;
+        let weapon = &player.weapon;
+
+        if layers.reach {
+            self.geng.draw2d().draw2d(
+                framebuffer,
+                camera,
+                &draw2d::Ellipse::circle_with_cut(
+                    player.position.as_f32(),
+                    weapon.reach.as_f32() - 0.03,
+                    weapon.reach.as_f32(),
+                    Color::new(1.0, 1.0, 1.0, 0.3),
+                ),
+            );
+        }
+
+        if let WeaponAction::Swing(swing) = &weapon.action {
+            let arc = swing.arc.map(R32::as_f32);
+            let to_world = |pos: vec2<f32>| player.position.as_f32() + pos;
+
+            if layers.arc {
+                let chain = Chain {
+                    vertices: arc.chain(50).vertices.into_iter().map(to_world).collect(),
+                };
+                self.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &draw2d::Chain::new(chain, 0.05, Color::BLUE, 0),
+                );
+            }
+
+            if layers.projection {
+                // Projection `update_weapon` steers to, there the pull also grows with the distance
+                let t = arc.project(weapon.position.as_f32());
+                let projection = arc.get(t);
+                let tangent = arc.tangent(t).normalize_or_zero();
+                let normal = projection - weapon.position.as_f32();
+
+                let projection = to_world(projection);
+                let segment = |from: vec2<f32>, delta: vec2<f32>, color: Color| {
+                    draw2d::Segment::new(Segment(from, from + delta), 0.03, color)
+                };
+                self.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &draw2d::Ellipse::circle(projection, 0.07, Color::YELLOW),
+                );
+                self.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &segment(projection, tangent * 0.5, Color::GREEN),
+                );
+                self.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &segment(to_world(weapon.position.as_f32()), normal, Color::MAGENTA),
+                );
+            }
+        }
+
+        if layers.colliders {
+            let colliders = model
+                .mannequins
+                .iter()
+                .map(|mannequin| mannequin.collider.clone())
+                .chain(
+                    model
+                        .projectiles
+                        .iter()
+                        .map(|projectile| projectile.collider.clone()),
+                )
+                .chain(model.fighters().flat_map(|fighter| {
+                    [
+                        fighter.collider(),
+                        fighter.blade_collider(),
+                        fighter.weapon_collider(),
+                    ]
+                }));
+            for collider in colliders {
+                self.draw_collider(&collider, Color::CYAN, camera, framebuffer);
+            }
+        }
+    }
+
+    pub fn draw_collider(
+        &self,
+        collider: &Collider,
+        color: Color,
+        camera: &impl geng::AbstractCamera2d,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let width = 0.03;
+        let transform = collider.transform_mat().map(R32::as_f32);
+        match collider.shape {
+            Shape::Circle { radius } => {
+                let radius = radius.as_f32();
+                self.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &draw2d::Ellipse::circle_with_cut(
+                        vec2::ZERO,
+                        (radius - width).max(0.0),
+                        radius,
+                        color,
+                    )
+                    .transform(transform),
+                );
+            }
+            Shape::Rectangle {
+                width: w,
+                height: h,
+            } => {
+                let aabb = Aabb2::ZERO.extend_symmetric(vec2(w, h).as_f32() / 2.0);
+                let mut vertices: Vec<vec2<f32>> = aabb
+                    .corners()
+                    .into_iter()
+                    .map(|p| (transform * p.extend(1.0)).into_2d())
+                    .collect();
+                vertices.push(vertices[0]);
+                self.geng.draw2d().draw2d(
+                    framebuffer,
+                    camera,
+                    &draw2d::Chain::new(Chain { vertices }, width, color, 0),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+struct FloatingText {
+    text: String,
+    position: vec2<f32>,
+    lifetime: Bounded<f32>,
+    initial_scale: f32,
+    rotation: Angle<f32>,
+}
+
+/// Short-lived text popups in the world, like damage numbers.
+pub struct FloatingTexts {
+    geng: Geng,
+    texts: Vec<FloatingText>,
+}
+
+impl FloatingTexts {
+    pub fn new(geng: &Geng) -> Self {
+        Self {
+            geng: geng.clone(),
+            texts: Vec::new(),
+        }
+    }
+
+    /// Spawn a text with a slight random tilt.
+    pub fn spawn(&mut self, text: impl Into<String>, position: vec2<f32>, lifetime: f32) {
+        let degrees = thread_rng().gen_range(-15.0..=15.0);
+        self.texts.push(FloatingText {
+            text: text.into(),
+            position,
+            lifetime: Bounded::new_max(lifetime),
+            initial_scale: 1.0,
+            rotation: Angle::from_degrees(degrees),
+        });
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for text in &mut self.texts {
+            text.lifetime.change(-delta_time);
+        }
+        self.texts.retain(|text| text.lifetime.is_above_min());
+    }
+
+    pub fn draw(&self, camera: &Camera2d, framebuffer: &mut ugli::Framebuffer) {
+        for text in &self.texts {
+            let t = text.lifetime.get_ratio();
+            let t = crate::util::smoothstep(t);
+            let scale = text.initial_scale * t;
+
+            let transform = mat3::translate(text.position)
+                * mat3::rotate(text.rotation)
+                * mat3::scale_uniform(scale);
+            let color = Color::lerp(Color::WHITE, Color::TRANSPARENT_WHITE, t);
+
+            self.geng.default_font().draw(
+                framebuffer,
+                camera,
+                &text.text,
+                vec2::splat(geng::TextAlign(0.5)),
+                transform,
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use super::*;
+
+/// Screen height in HUD units, everything on the screen is scaled to match it.
+pub const HUD_HEIGHT: f32 = 360.0;
+
+/// Camera of the overlays drawn in screen space,
+/// with the origin in the bottom-left corner and [HUD_HEIGHT] units tall.
+pub fn hud_camera(framebuffer_size: vec2<f32>) -> Camera2d {
+    Camera2d {
+        center: vec2(
+            framebuffer_size.x / framebuffer_size.y * HUD_HEIGHT,
+            HUD_HEIGHT,
+        ) / 2.0,
+        rotation: Angle::ZERO,
+        fov: HUD_HEIGHT,
+    }
+}
+
+/// Draws the player state on top of the game view in screen space.
+pub struct HudRender {
+    geng: Geng,
+}
+
+impl HudRender {
+    const FONT_SIZE: f32 = 12.0;
+    const MARGIN: f32 = 8.0;
+    const BAR_SIZE: vec2<f32> = vec2(100.0, 8.0);
+    /// Flashes per second of the full charge indicator.
+    const FLASH_RATE: f32 = 4.0;
+
+    pub fn new(geng: &Geng) -> Self {
+        Self { geng: geng.clone() }
+    }
+
+    pub fn draw(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let screen = framebuffer.size().as_f32();
+        let camera = hud_camera(screen);
+        let screen = Aabb2::ZERO.extend_positive(vec2(screen.x / screen.y, 1.0) * HUD_HEIGHT);
+        let screen = screen.extend_uniform(-Self::MARGIN);
+        let palette = &model.config.palette;
+        let player = &model.player;
+
+        // Health and stamina in the top-left
+        let pos = screen.top_left() - vec2(0.0, Self::BAR_SIZE.y);
+        self.draw_bar(
+            player.health.get_ratio(),
+            pos,
+            palette.health,
+            &camera,
+            framebuffer,
+        );
+        let pos = pos - vec2(0.0, Self::BAR_SIZE.y * 1.5);
+        self.draw_bar(
+            player.stamina.get_ratio(),
+            pos,
+            palette.stamina,
+            &camera,
+            framebuffer,
+        );
+
+        // Round timer at the top
+        let seconds = model.round_time.as_f32().max(0.0) as u64;
+        self.draw_text(
+            &format!("{}:{:02}", seconds / 60, seconds % 60),
+            vec2(screen.center().x, screen.max.y),
+            vec2(geng::TextAlign::CENTER, geng::TextAlign::TOP),
+            Color::WHITE,
+            &camera,
+            framebuffer,
+        );
+
+        // Respawn countdown in place of the charge meter
+        if let Some(death_time) = player.death_time {
+            let left = model.config.player.respawn_time - (model.game_time - death_time);
+            self.draw_text(
+                &format!("Down, back up in {:.1}", left.max(Time::ZERO)),
+                vec2(screen.center().x, screen.min.y),
+                vec2(geng::TextAlign::CENTER, geng::TextAlign::BOTTOM),
+                Color::WHITE,
+                &camera,
+                framebuffer,
+            );
+        }
+
+        // Weapon and combo in the top-right
+        let weapon = model.config.armed(player.weapon.name.as_deref());
+        let align = vec2(geng::TextAlign::RIGHT, geng::TextAlign::TOP);
+        self.draw_text(
+            &weapon.name,
+            screen.top_right(),
+            align,
+            Color::WHITE,
+            &camera,
+            framebuffer,
+        );
+        let combo = &player.combo;
+        if combo.count > 1 && model.game_time - combo.last_hit <= model.config.player.combo_window {
+            self.draw_text(
+                &format!("Combo x{}", combo.count),
+                screen.top_right() - vec2(0.0, Self::FONT_SIZE * 1.5),
+                align,
+                palette.charge,
+                &camera,
+                framebuffer,
+            );
+        }
+
+        // Charge meter below the player
+        if let Some(charge) = model.current_charge() {
+            let power = charge.power;
+            let range = (weapon.power_max - weapon.power_min).as_f32();
+            let ratio = if range > 0.0 {
+                (power - weapon.power_min).as_f32() / range
+            } else {
+                1.0
+            };
+            let (color, label) = match charge.state {
+                ChargeState::Charging => (palette.charge, "Power"),
+                ChargeState::Full => {
+                    // Flash to signal that there is no point in holding longer
+                    let flash = (model.real_time.as_f32() * Self::FLASH_RATE).fract() < 0.5;
+                    let color = if flash { Color::WHITE } else { palette.charge };
+                    (color, "Full power")
+                }
+                ChargeState::Overcharged => (Color::GRAY, "Overcharged"),
+            };
+            let pos = vec2(
+                screen.center().x - Self::BAR_SIZE.x / 2.0,
+                screen.min.y + Self::FONT_SIZE * 1.5,
+            );
+            self.draw_bar(ratio, pos, color, &camera, framebuffer);
+            self.draw_text(
+                &format!("{} {:.1}", label, power),
+                vec2(screen.center().x, screen.min.y),
+                vec2(geng::TextAlign::CENTER, geng::TextAlign::BOTTOM),
+                Color::WHITE,
+                &camera,
+                framebuffer,
+            );
+        }
+    }
+
+    /// Draw a bar with its bottom-left corner at `pos`, filled by `ratio`.
+    fn draw_bar(
+        &self,
+        ratio: impl Float,
+        pos: vec2<f32>,
+        color: Color,
+        camera: &Camera2d,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let ratio = ratio.as_f32().clamp(0.0, 1.0);
+        let back = Aabb2::point(pos).extend_positive(Self::BAR_SIZE);
+        let fill = Aabb2::point(pos).extend_positive(Self::BAR_SIZE * vec2(ratio, 1.0));
+        let draw2d = self.geng.draw2d();
+        draw2d.quad(framebuffer, camera, back, Color::new(0.0, 0.0, 0.0, 0.5));
+        draw2d.quad(framebuffer, camera, fill, color);
+    }
+
+    fn draw_text(
+        &self,
+        text: &str,
+        pos: vec2<f32>,
+        align: vec2<geng::TextAlign>,
+        color: Color,
+        camera: &Camera2d,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        self.geng.default_font().draw(
+            framebuffer,
+            camera,
+            text,
+            align,
+            mat3::translate(pos) * mat3::scale_uniform(Self::FONT_SIZE),
+            color,
+        );
+    }
+}
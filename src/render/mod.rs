@@ -1,14 +1,34 @@
+mod animation;
+mod debug;
+mod floating;
+mod hud;
 mod model;
+mod particles;
 mod util;
 
+pub use self::{
+    debug::DebugLayers,
+    hud::{hud_camera, HUD_HEIGHT},
+    util::UtilRender,
+};
+use self::{
+    debug::DebugRender, floating::FloatingTexts, hud::HudRender, particles::ParticleSystem,
+};
 use model::ModelRender;
-pub use util::UtilRender;
 
 use crate::prelude::*;
 
 pub struct GameRender {
     geng: Geng,
+    assets: Rc<Assets>,
     render: ModelRender,
+    particles: ParticleSystem,
+    texts: FloatingTexts,
+    /// Time until the next dust puff under the player.
+    dust_timer: f32,
+    debug: DebugRender,
+    hud: HudRender,
+    pub debug_layers: DebugLayers,
     pub framebuffer_size: vec2<usize>,
     pub texture: ugli::Texture,
     pub texture_target: Aabb2<f32>,
@@ -18,8 +38,14 @@ impl GameRender {
     pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
         Self {
             geng: geng.clone(),
-            // assets: assets.clone(),
+            assets: assets.clone(),
             render: ModelRender::new(geng, assets),
+            particles: ParticleSystem::new(geng),
+            texts: FloatingTexts::new(geng),
+            dust_timer: 0.0,
+            debug: DebugRender::new(geng),
+            hud: HudRender::new(geng),
+            debug_layers: DebugLayers::default(),
             framebuffer_size: vec2(1, 1),
             texture: {
                 let mut texture = geng_utils::texture::new_texture(geng.ugli(), vec2(640, 360));
@@ -30,22 +56,198 @@ impl GameRender {
         }
     }
 
+    pub fn handle_outcome(&mut self, outcome: &Outcome) {
+        match *outcome {
+            Outcome::SwingStarted {
+                stroke_center,
+                intent,
+                power,
+                ..
+            } => {
+                let name = match intent {
+                    WeaponIntent::Attack => "Slash",
+                    WeaponIntent::Defend => "Parry",
+                };
+                self.texts.spawn(
+                    format!("{} {}", name, power.round()),
+                    stroke_center.as_f32(),
+                    0.5,
+                );
+            }
+            Outcome::Hit {
+                position,
+                normal,
+                power,
+                damage,
+                backstab,
+                ..
+            } => {
+                // Stronger hits send particles flying faster
+                let intensity = power.as_f32().max(0.0).sqrt();
+                self.particles.spawn(
+                    &self.assets.particles.hit,
+                    position.as_f32(),
+                    normal.as_f32(),
+                    intensity,
+                );
+                self.texts.spawn(
+                    format!("{}", damage.as_f32().round() as i64),
+                    position.as_f32(),
+                    1.0,
+                );
+                if backstab {
+                    self.texts
+                        .spawn("Backstab", position.as_f32() + vec2(0.0, 0.5), 1.0);
+                }
+            }
+            Outcome::Parried {
+                position,
+                normal,
+                power,
+                ..
+            } => {
+                let intensity = power.as_f32().max(0.0).sqrt();
+                self.particles.spawn(
+                    &self.assets.particles.parry,
+                    position.as_f32(),
+                    normal.as_f32(),
+                    intensity,
+                );
+            }
+            Outcome::Clash {
+                position,
+                normal,
+                speed,
+                bound,
+            } => {
+                let intensity = (speed.as_f32() / 5.0).max(0.0).sqrt();
+                self.particles.spawn(
+                    &self.assets.particles.parry,
+                    position.as_f32(),
+                    normal.as_f32(),
+                    intensity,
+                );
+                let text = if bound { "Bind" } else { "Clash" };
+                self.texts.spawn(text, position.as_f32(), 0.5);
+            }
+            Outcome::Feinted { position } => {
+                self.texts.spawn("Feint", position.as_f32(), 0.5);
+            }
+            Outcome::Thrown { .. } => {}
+            Outcome::WallHit { position, normal } => {
+                self.particles.spawn(
+                    &self.assets.particles.dust,
+                    position.as_f32(),
+                    -normal.as_f32(),
+                    2.0,
+                );
+            }
+            Outcome::EffectApplied { position, effect } => {
+                let name = match effect {
+                    StatusEffect::Bleed { .. } => "Bleeding",
+                    StatusEffect::Stun => "Stunned",
+                    StatusEffect::Slow { .. } => "Slowed",
+                };
+                self.texts
+                    .spawn(name, position.as_f32() + vec2(0.0, 1.0), 1.0);
+            }
+            Outcome::Dashed {
+                position,
+                direction,
+            } => {
+                let feet = position.as_f32() - vec2(0.0, 0.4);
+                self.particles
+                    .spawn(&self.assets.particles.dust, feet, -direction.as_f32(), 3.0);
+            }
+            Outcome::Killed { position, .. } => {
+                self.particles.spawn(
+                    &self.assets.particles.death,
+                    position.as_f32(),
+                    vec2::ZERO,
+                    1.0,
+                );
+            }
+            Outcome::ComboStep { position, count } => {
+                self.texts.spawn(
+                    format!("x{}", count),
+                    position.as_f32() + vec2(0.0, 0.5),
+                    1.0,
+                );
+            }
+        }
+    }
+
+    pub fn update(&mut self, model: &Model, delta_time: f32) {
+        self.particles.update(delta_time);
+        self.texts.update(delta_time);
+
+        // Dust from walking
+        let player = &model.player;
+        self.dust_timer -= delta_time;
+        if self.dust_timer <= 0.0 && player.velocity.len().as_f32() > 1.0 {
+            self.dust_timer = 0.15;
+            let feet = player.position.as_f32() - vec2(0.0, 0.4);
+            self.particles.spawn(
+                &self.assets.particles.dust,
+                feet,
+                -player.velocity.as_f32(),
+                1.0,
+            );
+        }
+    }
+
     pub fn draw(&mut self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
         self.framebuffer_size = framebuffer.size();
 
+        let display = &model.config.display;
+        let resolution = display.resolution.map(|x| x.max(1));
+        if self.texture.size() != resolution {
+            geng_utils::texture::update_texture_size(
+                &mut self.texture,
+                resolution,
+                self.geng.ugli(),
+            );
+            self.texture.set_filter(ugli::Filter::Nearest);
+        }
+
         let screen = Aabb2::ZERO.extend_positive(framebuffer.size().as_f32());
-        self.texture_target =
-            geng_utils::layout::fit_aabb(self.texture.size().as_f32(), screen, vec2(0.5, 0.5));
+        let size = self.texture.size().as_f32();
+        self.texture_target = match display.scaling {
+            ScalingMode::PixelPerfect => {
+                let ratio = screen.size() / size;
+                let scale = ratio.x.min(ratio.y).floor().max(1.0);
+                geng_utils::layout::align_aabb(size * scale, screen, vec2(0.5, 0.5))
+            }
+            ScalingMode::Fit => geng_utils::layout::fit_aabb(size, screen, vec2(0.5, 0.5)),
+            ScalingMode::Stretch => screen,
+        };
 
         {
             let framebuffer =
                 &mut geng_utils::texture::attach_texture(&mut self.texture, self.geng.ugli());
             ugli::clear(framebuffer, Some(Color::BLACK), None, None);
             self.render.draw(model, framebuffer);
+            self.particles.draw(&model.camera, framebuffer);
+            self.texts.draw(&model.camera, framebuffer);
+            self.debug.draw(model, &self.debug_layers, framebuffer);
         }
 
         let mut draw = geng_utils::texture::DrawTexture::new(&self.texture);
         draw.target = self.texture_target;
         draw.draw(&geng::PixelPerfectCamera, &self.geng, framebuffer);
+
+        self.hud.draw(model, framebuffer);
+    }
+
+    /// Convert a mouse movement in screen pixels to world units.
+    pub fn screen_to_world_delta(&self, delta: vec2<f64>, camera: &Camera2d) -> vec2<f32> {
+        let texture_size = self.texture.size().as_f32();
+        let target_size = self.texture_target.size();
+        if target_size.x <= 0.0 || target_size.y <= 0.0 {
+            return vec2::ZERO;
+        }
+        // The camera sees `fov` units vertically across the whole texture
+        let world_size = vec2(camera.fov * texture_size.x / texture_size.y, camera.fov);
+        delta.as_f32() * world_size / target_size
     }
 }
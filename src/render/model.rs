@@ -14,37 +14,25 @@ impl ModelRender {
     }
 
     pub fn draw(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
-        let camera = &model.camera;
-
+        self.draw_arena(model, framebuffer);
         self.draw_mannequins(model, framebuffer);
-        self.draw_player(model, &model.player, framebuffer);
-        self.draw_cursor(model, &model.player.cursor, framebuffer);
-
-        for text in &model.floating_texts {
-            let t = text.lifetime.get_ratio().as_f32();
-            let t = crate::util::smoothstep(t);
-            let scale = text.initial_scale.as_f32() * t;
-
-            let transform = mat3::translate(text.pos.as_f32())
-                * mat3::rotate(text.rotation.map(R32::as_f32))
-                * mat3::scale_uniform(scale);
-            let color = Color::lerp(Color::WHITE, Color::TRANSPARENT_WHITE, t);
-
-            self.geng.default_font().draw(
-                framebuffer,
-                camera,
-                &text.text,
-                vec2::splat(geng::TextAlign(0.5)),
-                transform,
-                color,
-            );
+        self.draw_projectiles(model, framebuffer);
+        for opponent in &model.opponents {
+            self.draw_fighter(model, opponent, model.config.palette.opponent, framebuffer);
         }
+        self.draw_fighter(model, &model.player, Color::WHITE, framebuffer);
+        self.draw_cursor(model, &model.player.cursor, framebuffer);
+    }
 
-        // if let Some(action) = &self.player.weapon.action {
-        //     let chain = action.arc.map(R32::as_f32).chain(50);
-        //     let chain = draw2d::Chain::new(chain, 0.05, Rgba::BLUE, 0);
-        //     self.geng.draw2d().draw2d(framebuffer, &self.camera, &chain);
-        // }
+    pub fn draw_arena(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let arena = model.level.arena.map(R32::as_f32);
+        let mut vertices = arena.corners().to_vec();
+        vertices.push(vertices[0]);
+        self.geng.draw2d().draw2d(
+            framebuffer,
+            &model.camera,
+            &draw2d::Chain::new(Chain { vertices }, 0.1, Color::GRAY, 0),
+        );
     }
 
     pub fn draw_cursor(&self, model: &Model, cursor: &Cursor, framebuffer: &mut ugli::Framebuffer) {
@@ -55,10 +43,7 @@ impl ModelRender {
             .history
             .iter()
             .map(|entry| {
-                let t = (model.game_time - entry.time - model.config.cursor.fade_time)
-                    .max(Time::ZERO)
-                    / (model.config.cursor.trail_time - model.config.cursor.fade_time);
-                let t = crate::util::smoothstep(t).as_f32();
+                let t = trail_fade(&model.config.cursor, model.game_time - entry.time);
 
                 let mut color_a = match entry.state {
                     CursorState::Idle => Rgba::TRANSPARENT_BLACK, // self.config.palette.idle,
@@ -95,72 +80,172 @@ impl ModelRender {
     }
 
     pub fn draw_mannequins(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
-        for mannequin in &model.mannequins {
+        // Destroyed mannequins have burst into particles
+        for mannequin in model.mannequins.iter().filter(|m| m.is_alive()) {
             self.draw_mannequin(model, mannequin, framebuffer);
         }
     }
 
+    pub fn draw_projectiles(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        for projectile in &model.projectiles {
+            let config = model.config.projectiles.get(projectile.kind);
+            let position = projectile.collider.position.as_f32();
+            let direction = projectile.velocity.as_f32().normalize_or_zero();
+            let half = direction * config.length.as_f32() / 2.0;
+            self.geng.draw2d().draw2d(
+                framebuffer,
+                &model.camera,
+                &draw2d::Segment::new(
+                    Segment(position - half, position + half),
+                    config.radius.as_f32(),
+                    model.config.palette.projectile,
+                ),
+            );
+        }
+    }
+
     pub fn draw_mannequin(
         &self,
         model: &Model,
         mannequin: &Mannequin,
         framebuffer: &mut ugli::Framebuffer,
     ) {
-        let camera = &model.camera;
-        let texture = &self.assets.mannequin;
-
-        let pos = geng_utils::pixel::pixel_perfect_aabb(
-            mannequin.collider.position.as_f32(),
-            vec2(0.5, 0.5),
-            texture.size(),
-            camera,
-            framebuffer.size().as_f32(),
-        );
-
         let color_normal = Rgba::WHITE;
         let color_hit = Rgba::RED;
         let hit_t = (1.0 - (model.game_time - mannequin.hit_time).as_f32() / 0.5).clamp(0.0, 1.0);
         let hit_t = crate::util::smoothstep(hit_t);
         let color = Rgba::lerp(color_normal, color_hit, hit_t);
 
-        self.geng
-            .draw2d()
-            .textured_quad(framebuffer, camera, pos, texture, color);
+        let (state, time) = animation::mannequin_animation(model, mannequin);
+        self.draw_sprite(
+            model,
+            &self.assets.sprites.mannequin,
+            state,
+            time,
+            mannequin.collider.position,
+            mat3::identity(),
+            color,
+            framebuffer,
+        );
+        self.draw_effects(
+            model,
+            &mannequin.effects,
+            mannequin.collider.position,
+            framebuffer,
+        );
     }
 
-    pub fn draw_player(&self, model: &Model, player: &Player, framebuffer: &mut ugli::Framebuffer) {
-        let camera = &model.camera;
+    pub fn draw_fighter(
+        &self,
+        model: &Model,
+        fighter: &Fighter,
+        tint: Color,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let mut color = tint;
+        if fighter.is_invulnerable(model.game_time, &model.config.dash) {
+            color.a *= 0.5;
+        }
 
-        self.geng.draw2d().draw2d(
+        // The sprite looks to the right
+        let mirror = if fighter.facing.x < Coord::ZERO {
+            mat3::scale(vec2(-1.0, 1.0))
+        } else {
+            mat3::identity()
+        };
+
+        let (state, time) = animation::fighter_animation(model, fighter);
+        self.draw_sprite(
+            model,
+            &self.assets.sprites.player,
+            state,
+            time,
+            fighter.position,
+            mirror,
+            color,
             framebuffer,
-            camera,
-            &draw2d::Ellipse::circle(player.position.as_f32(), 0.5, Color::WHITE),
         );
 
-        self.draw_player_weapon(model, player.position, &player.weapon, framebuffer);
+        self.draw_fighter_weapon(model, fighter.position, &fighter.weapon, framebuffer);
+        self.draw_effects(model, &fighter.effects, fighter.position, framebuffer);
     }
 
-    pub fn draw_player_weapon(
+    /// Draw a row of markers above the entity, one per active status effect.
+    pub fn draw_effects(
         &self,
         model: &Model,
-        player_pos: vec2<Coord>,
-        weapon: &WeaponControl,
+        effects: &StatusEffects,
+        position: vec2<Coord>,
         framebuffer: &mut ugli::Framebuffer,
     ) {
-        let camera = &model.camera;
+        let palette = &model.config.palette;
+        let spacing = 0.2;
+        let start =
+            position.as_f32() + vec2(-(effects.active.len() as f32 - 1.0) * spacing / 2.0, 0.7);
+        for (i, active) in effects.active.iter().enumerate() {
+            let color = match active.effect {
+                StatusEffect::Bleed { .. } => palette.bleed,
+                StatusEffect::Stun => palette.stun,
+                StatusEffect::Slow { .. } => palette.slow,
+            };
+            self.geng.draw2d().circle(
+                framebuffer,
+                &model.camera,
+                start + vec2(i as f32 * spacing, 0.0),
+                0.07,
+                color,
+            );
+        }
+    }
 
-        // Position sprite
-        let offset = weapon.position;
-        let sword_pos = player_pos + offset;
-        let sword_pos = geng_utils::pixel::pixel_perfect_aabb(
-            sword_pos.as_f32(),
+    /// Draw the current frame of the animation pixel-perfectly centered at the position.
+    /// The `transform` is applied around the center, e.g. to rotate or mirror the sprite.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_sprite(
+        &self,
+        model: &Model,
+        sheet: &SpriteSheet,
+        state: AnimationState,
+        time: Time,
+        position: vec2<Coord>,
+        transform: mat3<f32>,
+        color: Color,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let camera = &model.camera;
+        let pos = geng_utils::pixel::pixel_perfect_aabb(
+            position.as_f32(),
             vec2(0.5, 0.5),
-            self.assets.sword.size(),
+            sheet.frame_size,
             camera,
             framebuffer.size().as_f32(),
         );
 
+        self.geng.draw2d().draw2d(
+            framebuffer,
+            camera,
+            &draw2d::TexturedQuad::colored(
+                Aabb2::ZERO.extend_symmetric(pos.size() / 2.0),
+                &sheet.texture,
+                color,
+            )
+            .sub_texture(sheet.frame_uv(state, time))
+            .transform(transform)
+            .translate(pos.center()),
+        );
+    }
+
+    pub fn draw_fighter_weapon(
+        &self,
+        model: &Model,
+        fighter_pos: vec2<Coord>,
+        weapon: &WeaponControl,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let camera = &model.camera;
+
         // Proper rotation
+        let offset = weapon.position;
         let mut angle = offset.as_f32().arg();
         if let WeaponAction::Swing(WeaponSwing {
             intent: WeaponIntent::Defend,
@@ -175,15 +260,16 @@ impl ModelRender {
         }
 
         // Render sprite
-        self.geng.draw2d().draw2d(
+        let (state, time) = animation::weapon_animation(model, weapon);
+        self.draw_sprite(
+            model,
+            &self.assets.sprites.sword,
+            state,
+            time,
+            fighter_pos + offset,
+            mat3::rotate(angle),
+            Color::WHITE,
             framebuffer,
-            camera,
-            &draw2d::TexturedQuad::new(
-                Aabb2::ZERO.extend_symmetric(sword_pos.size() / 2.0),
-                &*self.assets.sword,
-            )
-            .rotate(angle)
-            .translate(sword_pos.center()),
         );
 
         // Weapon trail
@@ -191,10 +277,7 @@ impl ModelRender {
             .history
             .iter()
             .map(|entry| {
-                let t = (model.game_time - entry.time - model.config.cursor.fade_time)
-                    .max(Time::ZERO)
-                    / (model.config.cursor.trail_time - model.config.cursor.fade_time);
-                let t = crate::util::smoothstep(t).as_f32();
+                let t = trail_fade(&model.config.cursor, model.game_time - entry.time);
 
                 let color_a = match entry.state {
                     CursorState::Idle => model.config.palette.idle,
@@ -216,3 +299,16 @@ impl ModelRender {
         );
     }
 }
+
+/// How much a trail entry of the given age has faded, from 0 to 1.
+fn trail_fade(config: &CursorConfig, age: Time) -> f32 {
+    let span = config.trail_time - config.fade_time;
+    let t = if span > Time::ZERO {
+        ((age - config.fade_time) / span).clamp(Time::ZERO, Time::ONE)
+    } else if age > config.fade_time {
+        Time::ONE
+    } else {
+        Time::ZERO
+    };
+    crate::util::smoothstep(t).as_f32()
+}
@@ -0,0 +1,105 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+    lifetime: Bounded<f32>,
+    emitter: Rc<Emitter>,
+}
+
+pub struct ParticleSystem {
+    geng: Geng,
+    particles: Vec<Particle>,
+}
+
+impl ParticleSystem {
+    pub fn new(geng: &Geng) -> Self {
+        Self {
+            geng: geng.clone(),
+            particles: Vec::new(),
+        }
+    }
+
+    /// Spawn particles from every emitter of the effect.
+    /// `intensity` scales the speed of the particles.
+    pub fn spawn(
+        &mut self,
+        effect: &[Emitter],
+        position: vec2<f32>,
+        direction: vec2<f32>,
+        intensity: f32,
+    ) {
+        let mut rng = thread_rng();
+        let direction = direction.normalize_or_zero();
+        for emitter in effect {
+            let emitter = Rc::new(emitter.clone());
+            for _ in 0..emitter.count {
+                let lifetime = gen_between(&mut rng, emitter.lifetime);
+                let speed = gen_between(&mut rng, emitter.speed) * intensity;
+                let angle =
+                    Angle::from_degrees(gen_between(&mut rng, (-emitter.spread, emitter.spread)));
+                let direction = if direction == vec2::ZERO {
+                    // No direction - emit in all directions
+                    vec2(1.0, 0.0).rotate(Angle::from_degrees(rng.gen_range(0.0..360.0)))
+                } else {
+                    direction.rotate(angle)
+                };
+                self.particles.push(Particle {
+                    position,
+                    velocity: direction * speed,
+                    lifetime: Bounded::new_max(lifetime),
+                    emitter: emitter.clone(),
+                });
+            }
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for particle in &mut self.particles {
+            particle.lifetime.change(-delta_time);
+            particle.position += particle.velocity * delta_time;
+            particle.velocity *= (1.0 - particle.emitter.drag * delta_time).max(0.0);
+        }
+        self.particles
+            .retain(|particle| particle.lifetime.is_above_min());
+    }
+
+    pub fn draw(&self, camera: &impl geng::AbstractCamera2d, framebuffer: &mut ugli::Framebuffer) {
+        for particle in &self.particles {
+            // Goes from 0 to 1 over the lifetime
+            let t = 1.0 - particle.lifetime.get_ratio();
+            let size = sample_curve(&particle.emitter.size, t, |a, b, t| a + (b - a) * t);
+            let color = sample_curve(&particle.emitter.color, t, Color::lerp);
+            let (Some(size), Some(color)) = (size, color) else {
+                continue;
+            };
+            self.geng.draw2d().draw2d(
+                framebuffer,
+                camera,
+                &draw2d::Quad::new(
+                    Aabb2::point(particle.position).extend_uniform(size / 2.0),
+                    color,
+                ),
+            );
+        }
+    }
+}
+
+/// Sample the keyframes evenly spread over `0..=1` at `t`.
+fn sample_curve<T: Copy>(keys: &[T], t: f32, lerp: impl Fn(T, T, f32) -> T) -> Option<T> {
+    match keys {
+        [] => None,
+        [key] => Some(*key),
+        _ => {
+            let t = t.clamp(0.0, 1.0) * (keys.len() - 1) as f32;
+            let i = (t.floor() as usize).min(keys.len() - 2);
+            Some(lerp(keys[i], keys[i + 1], t - i as f32))
+        }
+    }
+}
+
+/// Uniformly sample a value between the bounds given in any order.
+fn gen_between(rng: &mut impl Rng, (a, b): (f32, f32)) -> f32 {
+    rng.gen_range(a.min(b)..=a.max(b))
+}
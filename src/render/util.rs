@@ -6,10 +6,27 @@ pub struct UtilRender {
 }
 
 impl UtilRender {
-    pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+    pub fn new(geng: &Geng, _assets: &Rc<Assets>) -> Self {
         Self {
             geng: geng.clone(),
             // assets: assets.clone(),
         }
     }
+
+    /// Draw an error message in the top-left corner of the screen.
+    pub fn draw_error(&self, text: &str, framebuffer: &mut ugli::Framebuffer) {
+        let font_size = 20.0;
+        let pos = vec2(font_size, framebuffer.size().y as f32 - font_size);
+        for (i, line) in text.lines().enumerate() {
+            let pos = pos - vec2(0.0, i as f32 * font_size * 1.2);
+            self.geng.default_font().draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                line,
+                vec2(geng::TextAlign::LEFT, geng::TextAlign::CENTER),
+                mat3::translate(pos) * mat3::scale_uniform(font_size),
+                Color::RED,
+            );
+        }
+    }
 }
@@ -0,0 +1,383 @@
+use crate::prelude::*;
+
+/// Options of the headless bot-vs-bot matches.
+#[derive(clap::Args, Debug, Clone)]
+pub struct SimulationOpts {
+    /// Run the number of AI matches without a window and print the balance report.
+    #[clap(long, value_name = "MATCHES")]
+    pub simulate: Option<usize>,
+    /// Weapons of the two sides by name, from `weapon` or `arsenal` in the config.
+    #[clap(long, num_args = 2, value_names = ["FIRST", "SECOND"])]
+    pub weapons: Vec<String>,
+    /// Archetypes of the two sides by name, the first archetype by default.
+    #[clap(long, num_args = 2, value_names = ["FIRST", "SECOND"])]
+    pub archetypes: Vec<String>,
+    /// Fixed time step of the simulation in seconds.
+    #[clap(long, default_value_t = 1.0 / 60.0, value_parser = parse_seconds)]
+    pub step: f32,
+    /// Matches lasting longer than this many seconds are draws.
+    #[clap(long, default_value_t = 60.0, value_parser = parse_seconds)]
+    pub time_limit: f32,
+    /// Seed of the starting positions and the AI timings, the same seed gives the same results.
+    #[clap(long, default_value_t = 0)]
+    pub seed: u64,
+    /// Number of worker threads, all available cores by default.
+    #[clap(long)]
+    pub threads: Option<usize>,
+}
+
+/// A positive finite number of seconds.
+fn parse_seconds(value: &str) -> Result<f32, String> {
+    let seconds: f32 = value.parse().map_err(|err| format!("{}", err))?;
+    if seconds.is_finite() && seconds > 0.0 {
+        Ok(seconds)
+    } else {
+        Err(format!(
+            "must be a positive number of seconds, got {}",
+            value
+        ))
+    }
+}
+
+/// One of the two competing fighters.
+#[derive(Debug, Clone)]
+struct Side {
+    weapon: String,
+    archetype: String,
+}
+
+/// Result of a single match.
+#[derive(Debug, Clone)]
+struct MatchResult {
+    /// Index of the winning side, `None` on a timeout or a double knockout.
+    winner: Option<usize>,
+    time: Time,
+    /// Damage of every landed hit by each side.
+    hits: [Vec<Hp>; 2],
+    /// Total damage dealt by each side, bleeding included.
+    dealt: [Hp; 2],
+}
+
+/// Run the matches and print the report to the standard output.
+pub fn run(config: &Config, level: &Level, opts: &SimulationOpts) -> anyhow::Result<()> {
+    let matches = opts.simulate.unwrap_or(0);
+    let sides = sides(config, opts)?;
+    anyhow::ensure!(
+        opts.step > 0.0,
+        "--step must be positive, got {}",
+        opts.step
+    );
+    let threads = opts
+        .threads
+        .or_else(|| std::thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .clamp(1, matches.max(1));
+
+    let start = std::time::Instant::now();
+    let results: Vec<MatchResult> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|thread| {
+                let sides = &sides;
+                scope.spawn(move || {
+                    (thread..matches)
+                        .step_by(threads)
+                        .map(|index| play(config, level, sides, opts, index))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("simulation thread panicked"))
+            .collect()
+    });
+    log::info!(
+        "Simulated {} matches on {} threads in {:.2?}",
+        matches,
+        threads,
+        start.elapsed()
+    );
+
+    print!("{}", report(&sides, &results));
+    Ok(())
+}
+
+/// Resolve the weapon and the archetype names of the sides.
+fn sides(config: &Config, opts: &SimulationOpts) -> anyhow::Result<[Side; 2]> {
+    for name in &opts.weapons {
+        if config.weapon_by_name(name).is_none() {
+            let known: Vec<&str> = std::iter::once(&config.weapon)
+                .chain(&config.arsenal)
+                .map(|weapon| weapon.name.as_str())
+                .collect();
+            anyhow::bail!("Unknown weapon {:?}, expected one of {:?}", name, known);
+        }
+    }
+    for name in &opts.archetypes {
+        if config.ai.archetype(name).is_none() {
+            let known: Vec<&str> = config
+                .ai
+                .archetypes
+                .iter()
+                .map(|archetype| archetype.name.as_str())
+                .collect();
+            anyhow::bail!("Unknown archetype {:?}, expected one of {:?}", name, known);
+        }
+    }
+    let default_archetype = config
+        .ai
+        .archetypes
+        .first()
+        .map(|archetype| archetype.name.clone())
+        .ok_or_else(|| anyhow!("No AI archetypes in the config"))?;
+
+    let side = |i: usize| Side {
+        weapon: opts
+            .weapons
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| config.weapon.name.clone()),
+        archetype: opts
+            .archetypes
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| default_archetype.clone()),
+    };
+    Ok([side(0), side(1)])
+}
+
+/// Play a single match to the death or the time limit.
+fn play(
+    config: &Config,
+    level: &Level,
+    sides: &[Side; 2],
+    opts: &SimulationOpts,
+    index: usize,
+) -> MatchResult {
+    let mut rng = StdRng::seed_from_u64(opts.seed.wrapping_add(index as u64));
+    let mut model = Model::new(config.clone(), level.clone());
+    model.opponents.clear();
+    model.mannequins.clear();
+
+    // Start facing each other at a random spot of the arena
+    let arena = level.arena.map(Coord::as_f32);
+    let center = vec2(
+        rng.gen_range(arena.min.x..=arena.max.x),
+        rng.gen_range(arena.min.y..=arena.max.y),
+    ) * 0.5
+        + arena.center() * 0.5;
+    let offset =
+        vec2(rng.gen_range(1.5..=2.5), 0.0).rotate(Angle::from_degrees(rng.gen_range(0.0..360.0)));
+    let positions = [center - offset, center + offset].map(|pos| pos.as_r32());
+
+    // Alternate which side takes the player slot, it is special in a few places.
+    // Maps the sides to the fighter indices and back.
+    let swapped = index % 2 == 1;
+    let side_of = |fighter: usize| if swapped { 1 - fighter } else { fighter };
+    let [player, opponent] = [0, 1].map(|fighter| {
+        let side = &sides[side_of(fighter)];
+        let mut bot =
+            model.create_bot(positions[fighter], &side.archetype, FighterStats::default());
+        bot.weapon.name = Some(side.weapon.clone());
+        if let Some(brain) = &mut bot.brain {
+            brain.strafe_side = if rng.gen() { Coord::ONE } else { -Coord::ONE };
+        }
+        bot
+    });
+    model.player = player;
+    model.opponents.push(opponent);
+    model.rng = rng;
+    model.apply_config();
+    let health = |model: &Model, side: usize| {
+        model
+            .fighters()
+            .nth(side_of(side))
+            .map(|fighter| fighter.health)
+    };
+    let max_health =
+        [0, 1].map(|side| health(&model, side).map_or(Hp::ZERO, |health| health.max()));
+
+    let delta_time = r32(opts.step);
+    let time_limit = r32(opts.time_limit);
+    let mut hits = [Vec::new(), Vec::new()];
+    let mut killed = [false; 2];
+    while !killed.contains(&true) && model.round_time < time_limit {
+        model.update(delta_time);
+        for outcome in std::mem::take(&mut model.outcomes) {
+            match outcome {
+                Outcome::Hit {
+                    attacker, damage, ..
+                } => hits[side_of(attacker)].push(damage),
+                Outcome::Killed {
+                    fighter: Some(fighter),
+                    ..
+                } => killed[side_of(fighter)] = true,
+                _ => {}
+            }
+        }
+    }
+    // Both going down in the same step is a draw
+    let winner = match killed {
+        [false, true] => Some(0),
+        [true, false] => Some(1),
+        _ => None,
+    };
+
+    // A defeated opponent is gone, so the killed are counted as fully damaged
+    let dealt = [0, 1].map(|side| {
+        let target = 1 - side;
+        match health(&model, target) {
+            Some(health) if !killed[target] => max_health[target] - health.value(),
+            _ => max_health[target],
+        }
+    });
+    MatchResult {
+        winner,
+        time: model.round_time,
+        hits,
+        dealt,
+    }
+}
+
+/// Format the aggregated results as a table.
+fn report(sides: &[Side; 2], results: &[MatchResult]) -> String {
+    let matches = results.len().max(1) as f32;
+    let draws = results
+        .iter()
+        .filter(|result| result.winner.is_none())
+        .count();
+    let average_time = results
+        .iter()
+        .map(|result| result.time.as_f32())
+        .sum::<f32>()
+        / matches;
+
+    let mut report = String::new();
+    let mut line = |text: String| {
+        report.push_str(&text);
+        report.push('\n');
+    };
+    line(format!(
+        "{} matches, {} draws ({:.1}%), average length {:.2}s",
+        results.len(),
+        draws,
+        draws as f32 / matches * 100.0,
+        average_time
+    ));
+    line(String::new());
+    line(format!(
+        "{:<6} {:<12} {:<12} {:>7} {:>8} {:>8} {:>7} {:>8}  {}",
+        "Side",
+        "Weapon",
+        "Archetype",
+        "Wins",
+        "Win %",
+        "TTK",
+        "Hits",
+        "Dealt",
+        "Hit damage p10/p50/p90/max"
+    ));
+    for (i, side) in sides.iter().enumerate() {
+        let wins: Vec<&MatchResult> = results
+            .iter()
+            .filter(|result| result.winner == Some(i))
+            .collect();
+        // Time to kill counts only the matches the side has won
+        let ttk = if wins.is_empty() {
+            "-".to_string()
+        } else {
+            let total: f32 = wins.iter().map(|result| result.time.as_f32()).sum();
+            format!("{:.2}s", total / wins.len() as f32)
+        };
+        let mut damage: Vec<f32> = results
+            .iter()
+            .flat_map(|result| result.hits[i].iter().map(|hit| hit.as_f32()))
+            .collect();
+        damage.sort_by(f32::total_cmp);
+        let dealt: f32 = results.iter().map(|result| result.dealt[i].as_f32()).sum();
+        line(format!(
+            "{:<6} {:<12} {:<12} {:>7} {:>7.1}% {:>8} {:>7.1} {:>8.1}  {}",
+            i + 1,
+            side.weapon,
+            side.archetype,
+            wins.len(),
+            wins.len() as f32 / matches * 100.0,
+            ttk,
+            damage.len() as f32 / matches,
+            dealt / matches,
+            distribution(&damage),
+        ));
+    }
+    line(String::new());
+    line("Hits and Dealt are averages per match, Dealt includes bleeding.".to_string());
+    report
+}
+
+/// Percentiles of the sorted values.
+fn distribution(sorted: &[f32]) -> String {
+    let Some(&max) = sorted.last() else {
+        return "-".to_string();
+    };
+    let percentile = |p: f32| sorted[((sorted.len() - 1) as f32 * p).round() as usize];
+    format!(
+        "{:.1}/{:.1}/{:.1}/{:.1}",
+        percentile(0.1),
+        percentile(0.5),
+        percentile(0.9),
+        max
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(seed: u64) -> SimulationOpts {
+        SimulationOpts {
+            simulate: Some(8),
+            weapons: Vec::new(),
+            archetypes: Vec::new(),
+            step: 1.0 / 60.0,
+            time_limit: 20.0,
+            seed,
+            threads: Some(1),
+        }
+    }
+
+    /// Winners of the first few matches.
+    fn outcomes(seed: u64) -> Vec<Option<usize>> {
+        let config = Config::default();
+        let level = Level {
+            arena: Aabb2::ZERO.extend_symmetric(vec2(8.0, 5.0).as_r32()),
+            opponents: Vec::new(),
+            spawn_points: Vec::new(),
+        };
+        let opts = opts(seed);
+        let sides = sides(&config, &opts).unwrap();
+        (0..8)
+            .map(|index| play(&config, &level, &sides, &opts, index).winner)
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_matches() {
+        assert_eq!(outcomes(7), outcomes(7));
+    }
+
+    #[test]
+    fn seeds_vary_the_matches() {
+        assert_ne!(outcomes(0), outcomes(1000));
+    }
+
+    #[test]
+    fn distribution_of_no_values() {
+        assert_eq!(distribution(&[]), "-");
+    }
+
+    #[test]
+    fn distribution_picks_the_nearest_ranks() {
+        let values: Vec<f32> = (0..=10).map(|i| i as f32).collect();
+        assert_eq!(distribution(&values), "1.0/5.0/9.0/10.0");
+        assert_eq!(distribution(&[3.0]), "3.0/3.0/3.0/3.0");
+    }
+}